@@ -0,0 +1,37 @@
+use std::env;
+use std::process::ExitCode;
+
+use client::maze_solver::{compare, comparison_table};
+use server::maze::generators::Algorithm;
+
+const USAGE: &str = "Usage: compare_solvers [COLONNES LIGNES GRAINES]";
+
+/// Compare les solveurs hors ligne sur des labyrinthes générés par le serveur, de (0, 0) à la sortie.
+fn main() -> ExitCode {
+    let args: Vec<usize> = match env::args().skip(1).map(|arg| arg.parse()).collect() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let (columns, rows, seeds) = match args[..] {
+        [] => (12, 8, 4),
+        [columns, rows, seeds] => (columns, rows, seeds as u64),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut comparisons = Vec::new();
+    for algorithm in Algorithm::ALL {
+        for seed in 0..seeds {
+            let maze = algorithm.generate(columns, rows, seed);
+            let reports = compare(&maze.edges(), (0, 0), maze.exit());
+            comparisons.push((format!("{} {}", algorithm, seed), reports));
+        }
+    }
+    println!("{}", comparison_table(&comparisons));
+    ExitCode::SUCCESS
+}
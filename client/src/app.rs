@@ -1,10 +1,12 @@
 use eframe::{egui, App, Frame};
 use std::sync::mpsc::{Receiver, Sender};
-use crate::team_gui::{TeamRegistrationApp, RegistrationData};
-use crate::game_gui::GameView;
-use shared::messages::RelativeDirection;
+use crate::team_gui::{MemberStatus, TeamRegistrationApp, RegistrationData, RegistrationMode};
+use crate::game_gui::{self, GameView};
+use client::hints::HintBook;
+use client::session::SessionCommand;
+use client::strategy::ONLINE_SOLVERS;
+use shared::messages::{Action, ActionError, Challenge, Hint, RelativeDirection};
 use std::env;
-use std::path::Path;
 use image::io::Reader as ImageReader;
 use image::DynamicImage;
 use egui::TextureOptions;
@@ -26,23 +28,28 @@ pub enum AppState {
 
 #[derive(Debug)]
 pub enum NetworkMessage {
-    RegistrationComplete {
-        token: String,
-        team_name: String,
-        team_members: Vec<String>,
-    },
+    /// Équipe enregistrée ; ses membres sont inscrits dans la foulée.
+    TeamRegistered { token: String, expected_players: u8 },
     RegistrationFailed(String),
+    PlayerSubscribed(String),
+    PlayerRejected { player: String, reason: String },
+    /// Erreur du thread réseau, à afficher dans la zone de notifications.
+    Error(String),
     RadarViewReceived(String),
-    HintReceived(serde_json::Value),
-    ChallengeReceived(serde_json::Value),
-    ActionErrorReceived(String),
+    HintReceived { player: String, hint: Hint },
+    ChallengeReceived(Challenge),
+    ActionErrorReceived(ActionError),
 }
 
 pub struct MainApp {
+    /// Adresse `hôte:port` du serveur, utilisée par le thread réseau
+    pub server_address: String,
     pub state: AppState,
     pub team_registration: TeamRegistrationApp,
     pub rx_net: Receiver<NetworkMessage>,
     pub tx_gui_net: Sender<RegistrationData>,
+    /// Commandes à destination de la session du joueur
+    pub tx_session: Sender<SessionCommand>,
     /// Texture pour le fond par défaut ("random_maze.png")
     pub maze_texture: Option<egui::TextureHandle>,
     /// Texture pour le fond lors d'une action ("random_maze_in_action.png")
@@ -51,21 +58,83 @@ pub struct MainApp {
     pub rotation_angle: f32,
     /// Indique qu'une action de déplacement est en cours (direction et timestamp)
     pub active_move: Option<(RelativeDirection, f64)>,
+    /// Vue radar du jeu, mise à jour à chaque RadarView reçue
+    pub game_view: GameView,
+    /// Indices reçus par les joueurs de l'équipe
+    pub hint_book: HintBook,
+    /// Solveur du pilote automatique, `None` en jeu manuel
+    pub autopilot: Option<String>,
+    /// Erreurs affichées dans la zone de notifications, de la plus ancienne à la plus récente
+    pub notifications: Vec<String>,
 }
 
+/// Nombre de notifications gardées à l'écran ; les plus anciennes sont oubliées.
+const MAX_NOTIFICATIONS: usize = 5;
+
 impl MainApp {
-    pub fn new(rx_net: Receiver<NetworkMessage>, tx_gui_net: Sender<RegistrationData>) -> Self {
+    pub fn new(
+        server_address: String,
+        rx_net: Receiver<NetworkMessage>,
+        tx_gui_net: Sender<RegistrationData>,
+        tx_session: Sender<SessionCommand>,
+    ) -> Self {
         Self {
+            server_address,
             state: AppState::Registration,
             team_registration: TeamRegistrationApp::default(),
             rx_net,
             tx_gui_net,
+            tx_session,
             maze_texture: None,
             in_game_texture: None,
             rotation_angle: 0.0,
             active_move: None,
+            game_view: GameView::default(),
+            hint_book: HintBook::default(),
+            autopilot: None,
+            notifications: Vec::new(),
+        }
+    }
+
+    /// Ajoute une erreur à la zone de notifications.
+    pub fn notify(&mut self, message: impl Into<String>) {
+        self.notifications.push(message.into());
+        if self.notifications.len() > MAX_NOTIFICATIONS {
+            self.notifications.remove(0);
+        }
+    }
+
+    /// Déclenche un déplacement du joueur via sa session.
+    fn send_move(&mut self, dir: RelativeDirection, time: f64) {
+        self.active_move = Some((dir.clone(), time));
+        if let Err(e) = self.tx_session.send(SessionCommand::Action(Action::MoveTo(dir))) {
+            self.notify(format!("Erreur lors de l'envoi du déplacement: {}", e));
         }
     }
+
+    /// Affiche les notifications en bas de la fenêtre, chacune avec un bouton pour la fermer.
+    fn notifications_panel(&mut self, ctx: &egui::Context) {
+        if self.notifications.is_empty() {
+            return;
+        }
+        egui::TopBottomPanel::bottom("notifications").show(ctx, |ui| {
+            let mut dismissed = None;
+            for (i, message) in self.notifications.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.small_button("✖").clicked() {
+                        dismissed = Some(i);
+                    }
+                    ui.label(egui::RichText::new(message).color(egui::Color32::LIGHT_RED));
+                });
+            }
+            if let Some(i) = dismissed {
+                self.notifications.remove(i);
+            }
+            if ui.button("Tout effacer").clicked() {
+                self.notifications.clear();
+            }
+        });
+    }
 }
 
 /// Charge une texture depuis un chemin relatif, en construisant un chemin absolu basé sur le répertoire courant.
@@ -92,7 +161,7 @@ fn arrow_button(ui: &mut egui::Ui, label: &str, active: bool) -> egui::Response
 }
 
 impl App for MainApp {
-    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
         // Charger les textures si nécessaire.
         if self.maze_texture.is_none() {
             self.maze_texture = load_texture(ctx, "images/random_maze.png");
@@ -115,36 +184,79 @@ impl App for MainApp {
         // Traiter les messages réseau.
         while let Ok(msg) = self.rx_net.try_recv() {
             match msg {
-                NetworkMessage::RegistrationComplete { token, team_name, team_members } => {
-                    self.state = AppState::GameSetup { team_name, team_members, token };
+                NetworkMessage::TeamRegistered { token, expected_players } => {
+                    self.team_registration.on_team_registered(token, expected_players);
                 }
                 NetworkMessage::RegistrationFailed(err) => {
-                    eprintln!("Registration failed: {}", err);
+                    self.team_registration.on_team_failed(err);
+                }
+                NetworkMessage::PlayerSubscribed(player) => {
+                    self.team_registration.on_player_result(&player, MemberStatus::Subscribed);
+                }
+                NetworkMessage::PlayerRejected { player, reason } => {
+                    self.team_registration.on_player_result(&player, MemberStatus::Rejected(reason));
+                }
+                NetworkMessage::Error(err) => {
+                    self.notify(err);
                 }
                 NetworkMessage::RadarViewReceived(rv) => {
-                    println!("RadarView reçue: {}", rv);
+                    if let Err(e) = self.game_view.set_radar(&rv) {
+                        self.notify(format!("RadarView invalide ({}): {}", e, rv));
+                    }
                 }
-                NetworkMessage::HintReceived(hint) => {
-                    println!("Hint reçu: {:?}", hint);
+                NetworkMessage::HintReceived { player, hint } => {
+                    self.hint_book.record(&player, &hint);
                 }
                 NetworkMessage::ChallengeReceived(challenge) => {
-                    println!("Challenge reçu: {:?}", challenge);
+                    self.notify(match challenge {
+                        Challenge::SecretSumModulo(modulo) => {
+                            format!("Challenge : somme des secrets de l'équipe modulo {}", modulo)
+                        }
+                        Challenge::SOS => "Challenge SOS : un coéquipier doit venir sur votre case".to_string(),
+                    });
                 }
                 NetworkMessage::ActionErrorReceived(err) => {
-                    eprintln!("ActionError reçu: {}", err);
+                    self.notify(format!("Action refusée: {:?}", err));
                 }
             }
         }
 
+        // Les panneaux du bas passent avant le panneau central.
+        self.notifications_panel(ctx);
+
         match &self.state {
             AppState::Registration => {
+                let mut send_error = None;
+                let mut started = false;
+                let mut reset = false;
                 egui::CentralPanel::default().show(ctx, |ui| {
                     self.team_registration.ui(ui, |reg_data| {
+                        started |= matches!(reg_data, RegistrationData::Start);
+                        reset |= matches!(reg_data, RegistrationData::Reset);
                         if let Err(e) = self.tx_gui_net.send(reg_data) {
-                            eprintln!("Erreur lors de l'envoi via le canal: {}", e);
+                            send_error = Some(format!("Erreur lors de l'envoi via le canal: {}", e));
                         }
                     });
                 });
+                if reset {
+                    // Les indices et la vue radar venaient des sessions qui viennent d'être fermées.
+                    self.hint_book = HintBook::default();
+                    self.game_view.radar = None;
+                }
+                if let Some(error) = send_error {
+                    self.notify(error);
+                } else if started {
+                    let registration = &self.team_registration;
+                    let team_name = match registration.mode {
+                        RegistrationMode::Create => registration.team_name.clone(),
+                        RegistrationMode::Join => String::new(),
+                    };
+                    self.state = AppState::GameSetup {
+                        team_name,
+                        team_members: registration.subscribed_players(),
+                        token: registration.token.clone().unwrap_or_default(),
+                    };
+                }
             }
             AppState::GameSetup { team_name, team_members, token } => {
                 let team_name_cl = team_name.clone();
@@ -167,6 +279,9 @@ impl App for MainApp {
                             ui.label(egui::RichText::new("Token :").size(20.0).color(egui::Color32::LIGHT_GRAY));
                             ui.label(egui::RichText::new(&token_cl).size(22.0).color(egui::Color32::WHITE));
                             ui.separator();
+                            ui.label(egui::RichText::new("Serveur :").size(20.0).color(egui::Color32::LIGHT_GRAY));
+                            ui.label(egui::RichText::new(&self.server_address).size(22.0).color(egui::Color32::WHITE));
+                            ui.separator();
                             ui.add_space(20.0);
                             // Bouton pour lancer la partie.
                             ui.horizontal(|ui| {
@@ -243,8 +358,31 @@ impl App for MainApp {
                         }
                         ui.separator();
                         ui.label(egui::RichText::new(format!("Token : {}", token)).size(18.0).color(egui::Color32::WHITE));
+                        ui.separator();
+                        ui.label(egui::RichText::new(format!("Serveur : {}", self.server_address)).size(18.0).color(egui::Color32::WHITE));
+                        ui.separator();
+                        if ui.checkbox(&mut self.game_view.demo_mode, "Mode démo").changed() && !self.game_view.demo_mode {
+                            self.game_view.radar = None;
+                        }
+                        ui.separator();
+                        let previous = self.autopilot.clone();
+                        egui::ComboBox::from_label("Pilote automatique")
+                            .selected_text(self.autopilot.as_deref().unwrap_or("manuel"))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.autopilot, None, "manuel");
+                                for name in ONLINE_SOLVERS {
+                                    ui.selectable_value(&mut self.autopilot, Some(name.to_string()), name);
+                                }
+                            });
+                        if self.autopilot != previous {
+                            self.tx_session.send(SessionCommand::Autopilot(self.autopilot.clone())).ok();
+                        }
                     });
                 });
+                // Panneau latéral : indices reçus.
+                egui::SidePanel::right("hints").show(ctx, |ui| {
+                    game_gui::hints_panel(ui, &self.hint_book);
+                });
                 // Panneau central : affichage du fond et de la vue radar.
                 egui::CentralPanel::default().show(ctx, |ui| {
                     let background_tex = if self.active_move.is_some() {
@@ -260,7 +398,7 @@ impl App for MainApp {
                         egui::Rect::from_min_size(egui::Pos2::ZERO, background_tex.size_vec2()),
                         egui::Color32::WHITE,
                     );
-                    GameView::default().update(ctx, frame);
+                    self.game_view.ui(ui);
                 });
                 // Panneau de contrôles en bas.
                 egui::TopBottomPanel::bottom("move_controls").show(ctx, |ui| {
@@ -268,33 +406,24 @@ impl App for MainApp {
                     let active_dir = self.active_move.as_ref().map(|(d, _)| d.clone());
                     ui.horizontal_centered(|ui| {
                         if arrow_button(ui, "⬅️", active_dir == Some(RelativeDirection::Left)).clicked() {
-                            self.active_move = Some((RelativeDirection::Left, current_time));
-                            crate::game::GameClient::send_move_action_static("127.0.0.1:8778", RelativeDirection::Left);
+                            self.send_move(RelativeDirection::Left, current_time);
                         }
                         if arrow_button(ui, "⬆️", active_dir == Some(RelativeDirection::Front)).clicked() {
-                            self.active_move = Some((RelativeDirection::Front, current_time));
-                            crate::game::GameClient::send_move_action_static("127.0.0.1:8778", RelativeDirection::Front);
+                            self.send_move(RelativeDirection::Front, current_time);
                         }
                         if arrow_button(ui, "➡️", active_dir == Some(RelativeDirection::Right)).clicked() {
-                            self.active_move = Some((RelativeDirection::Right, current_time));
-                            crate::game::GameClient::send_move_action_static("127.0.0.1:8778", RelativeDirection::Right);
+                            self.send_move(RelativeDirection::Right, current_time);
                         }
                         if arrow_button(ui, "⬇️", active_dir == Some(RelativeDirection::Back)).clicked() {
-                            self.active_move = Some((RelativeDirection::Back, current_time));
-                            crate::game::GameClient::send_move_action_static("127.0.0.1:8778", RelativeDirection::Back);
+                            self.send_move(RelativeDirection::Back, current_time);
                         }
                     });
                 });
             }
         }
     }
-}
 
-fn load_title_image(ctx: &egui::Context, path: &str) -> Option<egui::TextureHandle> {
-    let img = ImageReader::open(Path::new(path)).ok()?.decode().ok()?;
-    let rgba = img.to_rgba8();
-    let size = [rgba.width() as usize, rgba.height() as usize];
-    let pixels = rgba.into_raw();
-    let color_image = egui::ColorImage::from_rgba_unmultiplied(size, &pixels);
-    Some(ctx.load_texture("team_title", color_image, TextureOptions::default()))
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.tx_session.send(SessionCommand::Shutdown).ok();
+    }
 }
@@ -0,0 +1,94 @@
+use std::env;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use client::bot::{self, BotConfig};
+use shared::config::{self, ServerAddress};
+
+const USAGE: &str = "[--team NOM] [--players N] [--strategy NOM] [--max-moves N] [--seed N] [--coordinated]";
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<BotConfig, String> {
+    let (address, rest) = ServerAddress::from_env_and_args(args).map_err(|e| e.to_string())?;
+    let mut config = BotConfig { server_address: address.to_string(), ..BotConfig::default() };
+    let mut args = rest.into_iter();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("valeur manquante pour {}", flag));
+        match flag.as_str() {
+            "--team" => config.team_name = value()?,
+            "--strategy" => config.strategy = value()?,
+            "--players" => config.players = value()?.parse().map_err(|e| format!("--players: {}", e))?,
+            "--max-moves" => config.max_moves = value()?.parse().map_err(|e| format!("--max-moves: {}", e))?,
+            "--seed" => config.seed = value()?.parse().map_err(|e| format!("--seed: {}", e))?,
+            "--coordinated" => config.coordinated = true,
+            other => return Err(format!("option inconnue: {}", other)),
+        }
+    }
+    Ok(config)
+}
+
+fn main() -> ExitCode {
+    let config = match parse_args(env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}\nUsage: bot {} {}", e, config::USAGE, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let strategy = if config.coordinated { "frontier (coordonnée)" } else { config.strategy.as_str() };
+    println!(
+        "Équipe {} : {} joueur(s), stratégie {}, serveur {}",
+        config.team_name, config.players, strategy, config.server_address
+    );
+    let start = Instant::now();
+    let reports = match bot::run(&config) {
+        Ok(reports) => reports,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!(
+        "{:<20} {:>8} {:>8} {:>8} {:>8} {:>10}",
+        "joueur", "sortie", "coups", "attentes", "erreurs", "temps (ms)"
+    );
+    for report in &reports {
+        println!(
+            "{:<20} {:>8} {:>8} {:>8} {:>8} {:>10}",
+            report.player_name,
+            if report.reached_target { "oui" } else { "non" },
+            report.moves,
+            report.waits,
+            report.error_count(),
+            report.elapsed.as_millis(),
+        );
+        for (error, count) in &report.errors {
+            println!("    {:<30} {}", error, count);
+        }
+        if report.sos + report.helper_calls + report.rescues > 0 {
+            println!(
+                "    SOS reçus: {}, appels à l'aide: {}, coéquipiers secourus: {}",
+                report.sos, report.helper_calls, report.rescues
+            );
+        }
+        if let Some(failure) = &report.failure {
+            println!("    session interrompue: {}", failure);
+        }
+    }
+    let escaped = reports.iter().filter(|r| r.reached_target).count();
+    let moves: u32 = reports.iter().map(|r| r.moves).sum();
+    let errors: u32 = reports.iter().map(|r| r.error_count()).sum();
+    println!(
+        "Total : {}/{} sortis, {} coups, {} erreurs, {} ms",
+        escaped,
+        reports.len(),
+        moves,
+        errors,
+        start.elapsed().as_millis()
+    );
+    if escaped == reports.len() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,86 @@
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use client::loadtest::{self, LoadConfig, RATE_RANGE};
+use shared::config::{self, ServerAddress};
+
+const USAGE: &str = "[--teams K] [--players N] [--strategy NOM] [--rate TOURS/S] [--max-moves N] \
+                     [--duration SECONDES] [--seed N] [--prefix NOM] [--json FICHIER|-]";
+
+/// Chemin du bilan JSON ; `-` l'écrit sur la sortie standard à la place du tableau.
+type JsonOutput = Option<String>;
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<(LoadConfig, JsonOutput), String> {
+    let (address, rest) = ServerAddress::from_env_and_args(args).map_err(|e| e.to_string())?;
+    let mut config = LoadConfig { server_address: address.to_string(), ..LoadConfig::default() };
+    let mut json = None;
+    let mut args = rest.into_iter();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("valeur manquante pour {}", flag));
+        match flag.as_str() {
+            "--teams" => config.teams = value()?.parse().map_err(|e| format!("--teams: {}", e))?,
+            "--players" => config.players = value()?.parse().map_err(|e| format!("--players: {}", e))?,
+            "--strategy" => config.strategy = value()?,
+            "--rate" => {
+                let raw = value()?;
+                let rate: f64 = raw.parse().map_err(|e| format!("--rate: {}", e))?;
+                if rate != 0.0 && !RATE_RANGE.contains(&rate) {
+                    return Err(format!(
+                        "--rate: {} hors de [{}, {}] (0 : sans limite)",
+                        raw,
+                        RATE_RANGE.start(),
+                        RATE_RANGE.end()
+                    ));
+                }
+                config.rate = rate;
+            }
+            "--max-moves" => config.max_moves = value()?.parse().map_err(|e| format!("--max-moves: {}", e))?,
+            "--duration" => {
+                let seconds = value()?.parse().map_err(|e| format!("--duration: {}", e))?;
+                config.duration = Some(Duration::from_secs(seconds));
+            }
+            "--seed" => config.seed = value()?.parse().map_err(|e| format!("--seed: {}", e))?,
+            "--prefix" => config.team_prefix = value()?,
+            "--json" => json = Some(value()?),
+            other => return Err(format!("option inconnue: {}", other)),
+        }
+    }
+    Ok((config, json))
+}
+
+fn main() -> ExitCode {
+    let (config, json) = match parse_args(env::args().skip(1)) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}\nUsage: loadtest {} {}", e, config::USAGE, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!(
+        "{} équipe(s) de {} joueur(s), stratégie {}, serveur {}",
+        config.teams, config.players, config.strategy, config.server_address
+    );
+    let report = match loadtest::run(&config) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let encoded = serde_json::to_string_pretty(&report).expect("bilan sérialisable");
+    match json.as_deref() {
+        Some("-") => println!("{}", encoded),
+        Some(path) => {
+            println!("{}", report.table());
+            if let Err(e) = fs::write(path, encoded + "\n") {
+                eprintln!("❌ écriture de {} impossible: {}", path, e);
+                return ExitCode::FAILURE;
+            }
+        }
+        None => println!("{}", report.table()),
+    }
+    ExitCode::SUCCESS
+}
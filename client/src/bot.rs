@@ -0,0 +1,443 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::challenge::perform_timed;
+use crate::coordinator::Coordinator;
+use crate::error::ClientError;
+use crate::game::GameClient;
+use crate::hints::HintBook;
+use crate::session::{last_radar, PlayerSession, SessionEvent};
+use crate::strategy::{self, Strategy};
+use shared::config::ServerAddress;
+use shared::messages::{Action, Challenge, Hint};
+use shared::radar::RadarGrid;
+
+/// Durée d'un tour passé à attendre.
+const WAIT_DELAY: Duration = Duration::from_millis(20);
+
+/// Paramètres d'une partie jouée par le bot.
+#[derive(Debug, Clone)]
+pub struct BotConfig {
+    pub server_address: String,
+    pub team_name: String,
+    pub players: usize,
+    pub strategy: String,
+    /// Nombre maximal de tours par joueur : déplacements tentés et attentes.
+    pub max_moves: u32,
+    pub seed: u64,
+    /// Fait jouer l'équipe ensemble, avec une carte partagée, au lieu de joueurs indépendants.
+    /// `strategy` est alors ignorée : le coordinateur explore toujours comme `frontier`.
+    pub coordinated: bool,
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        Self {
+            server_address: ServerAddress::default().to_string(),
+            team_name: "bots".to_string(),
+            players: 3,
+            strategy: "right-hand".to_string(),
+            max_moves: 10_000,
+            seed: 0,
+            coordinated: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BotError {
+    UnknownStrategy(String),
+    Registration(String, ClientError),
+    Session(String, ClientError),
+}
+
+impl fmt::Display for BotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BotError::UnknownStrategy(name) => {
+                write!(f, "stratégie inconnue: {} (disponibles: {})", name, strategy::names().join(", "))
+            }
+            BotError::Registration(team, e) => write!(f, "équipe {}: {}", team, e),
+            BotError::Session(player, e) => write!(f, "session de {}: {}", player, e),
+        }
+    }
+}
+
+impl std::error::Error for BotError {}
+
+/// Bilan d'un joueur à la fin de la partie.
+#[derive(Debug, Clone)]
+pub struct PlayerReport {
+    pub player_name: String,
+    /// Déplacements envoyés, acceptés ou non.
+    pub moves: u32,
+    /// Tours passés sans bouger, à attendre qu'un adversaire ou un monstre s'écarte.
+    pub waits: u32,
+    /// Nombre d'erreurs par variante d'`ActionError`.
+    pub errors: BTreeMap<String, u32>,
+    pub reached_target: bool,
+    pub elapsed: Duration,
+    /// Erreur qui a interrompu la session, le cas échéant.
+    pub failure: Option<String>,
+    /// Challenges `SOS` reçus.
+    pub sos: u32,
+    /// `Hint::SOSHelper` reçus : un coéquipier en SOS attendait de l'aide.
+    pub helper_calls: u32,
+    /// Coéquipiers en SOS rejoints et libérés par le joueur.
+    pub rescues: u32,
+}
+
+impl PlayerReport {
+    pub(crate) fn new(player_name: String, reached_target: bool) -> Self {
+        Self {
+            player_name,
+            moves: 0,
+            waits: 0,
+            errors: BTreeMap::new(),
+            reached_target,
+            elapsed: Duration::ZERO,
+            failure: None,
+            sos: 0,
+            helper_calls: 0,
+            rescues: 0,
+        }
+    }
+
+    /// Compte les erreurs d'action, les `SOS` et les appels à l'aide d'une réponse du serveur.
+    pub(crate) fn record(&mut self, events: &[SessionEvent]) {
+        for event in events {
+            match event {
+                SessionEvent::ActionError(error) => *self.errors.entry(format!("{:?}", error)).or_insert(0) += 1,
+                SessionEvent::Challenge(Challenge::SOS) => self.sos += 1,
+                SessionEvent::Hint(Hint::SOSHelper) => self.helper_calls += 1,
+                _ => {}
+            }
+        }
+    }
+
+    pub fn error_count(&self) -> u32 {
+        self.errors.values().sum()
+    }
+}
+
+/// Joueur inscrit, avec les événements reçus à l'inscription et sa première vue radar.
+pub(crate) type Subscribed = (PlayerSession, RadarGrid, Vec<SessionEvent>);
+
+/// Mesures prises pendant une partie : la durée de chaque requête aboutie, par type
+/// (`RegisterTeam`, `SubscribePlayer`, `MoveTo`, `SolveChallenge`), et l'erreur qui interrompt une session.
+pub(crate) trait RequestHook {
+    fn on_request(&mut self, _kind: &'static str, _elapsed: Duration) {}
+
+    fn on_failure(&mut self, _error: &ClientError) {}
+}
+
+impl RequestHook for () {}
+
+/// Exécute une requête et transmet sa durée au crochet si elle aboutit.
+pub(crate) fn timed<T>(
+    hook: &mut impl RequestHook,
+    kind: &'static str,
+    request: impl FnOnce() -> Result<T, ClientError>,
+) -> Result<T, ClientError> {
+    let start = Instant::now();
+    let result = request();
+    if result.is_ok() {
+        hook.on_request(kind, start.elapsed());
+    }
+    result
+}
+
+/// Rythme des joueurs lancés par [`run_with`].
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Pace {
+    /// Intervalle minimal entre deux tours d'un joueur ; sans intervalle, il rejoue dès la réponse reçue.
+    pub(crate) interval: Option<Duration>,
+    /// Aucun tour n'est commencé après cet instant.
+    pub(crate) deadline: Option<Instant>,
+}
+
+/// Inscrit l'équipe puis tous ses joueurs, avant le premier déplacement pour connaître tous les secrets.
+pub(crate) fn subscribe_team(
+    config: &BotConfig,
+    hook: &mut impl RequestHook,
+) -> Result<(HintBook, Vec<Subscribed>), BotError> {
+    let token = GameClient::new(&config.server_address)
+        .and_then(|mut client| timed(hook, "RegisterTeam", || client.register_team(&config.team_name)))
+        .map_err(|e| BotError::Registration(config.team_name.clone(), e))?
+        .registration_token;
+
+    let mut hints = HintBook::default();
+    let mut players = Vec::new();
+    for i in 0..config.players {
+        let name = format!("{}-{}", config.team_name, i);
+        let (session, radar, events) =
+            timed(hook, "SubscribePlayer", || PlayerSession::subscribe(&config.server_address, &token, &name))
+                .map_err(|e| BotError::Session(name.clone(), e))?;
+        hints.observe(&name, &events);
+        players.push((session, radar, events));
+    }
+    Ok((hints, players))
+}
+
+/// Inscrit l'équipe et ses joueurs, puis fait jouer chaque joueur dans son propre thread
+/// jusqu'à la sortie ou jusqu'à `max_moves` déplacements. Avec `coordinated`, toute l'équipe
+/// est confiée à un [`Coordinator`] qui la fait jouer dans le thread appelant, sans consulter `strategy`.
+pub fn run(config: &BotConfig) -> Result<Vec<PlayerReport>, BotError> {
+    if config.coordinated {
+        let (hints, players) = subscribe_team(config, &mut ())?;
+        return Ok(Coordinator::new(hints, players).run(config.max_moves));
+    }
+    let reports = run_with::<()>(config, Pace::default(), &mut ())?;
+    Ok(reports.into_iter().map(|(report, _)| report).collect())
+}
+
+/// Comme [`run`] sans coordination, au rythme de `pace`. `hook` mesure l'inscription ;
+/// chaque joueur a son propre crochet, rendu avec son bilan.
+/// Les sessions ne sont fermées qu'une fois toute l'équipe arrêtée : le serveur compte le secret
+/// d'un joueur sorti dans les challenges tant que sa connexion reste ouverte.
+pub(crate) fn run_with<H: RequestHook + Default + Send + 'static>(
+    config: &BotConfig,
+    pace: Pace,
+    hook: &mut H,
+) -> Result<Vec<(PlayerReport, H)>, BotError> {
+    if strategy::by_name(&config.strategy, 0).is_none() {
+        return Err(BotError::UnknownStrategy(config.strategy.clone()));
+    }
+    let (hints, players) = subscribe_team(config, hook)?;
+    let hints = Arc::new(Mutex::new(hints));
+
+    let handles: Vec<_> = players.into_iter().enumerate()
+        .map(|(i, player)| {
+            let strategy = strategy::by_name(&config.strategy, config.seed.wrapping_add(i as u64))
+                .expect("stratégie vérifiée");
+            let hints = hints.clone();
+            let max_moves = config.max_moves;
+            thread::spawn(move || {
+                let mut hook = H::default();
+                let (session, report) = play(player, strategy, &hints, max_moves, pace, &mut hook);
+                (session, report, hook)
+            })
+        })
+        .collect();
+    let mut sessions = Vec::new();
+    let mut reports = Vec::new();
+    for handle in handles {
+        let (session, report, hook) = handle.join().expect("thread de joueur");
+        sessions.push(session);
+        reports.push((report, hook));
+    }
+    for session in sessions {
+        session.shutdown().ok();
+    }
+    Ok(reports)
+}
+
+/// Boucle de jeu d'un joueur. La session est rendue pour être fermée avec celles de l'équipe.
+fn play(
+    (mut session, mut radar, initial_events): Subscribed,
+    mut strategy: Box<dyn Strategy>,
+    hints: &Mutex<HintBook>,
+    max_moves: u32,
+    pace: Pace,
+    hook: &mut impl RequestHook,
+) -> (PlayerSession, PlayerReport) {
+    let start = Instant::now();
+    for event in &initial_events {
+        if let SessionEvent::Hint(hint) = event {
+            strategy.on_hint(hint);
+        }
+    }
+    let mut report = PlayerReport::new(session.player_name.clone(), strategy::reached_target(&radar));
+    let mut next_turn = Instant::now();
+    while !report.reached_target
+        && report.moves + report.waits < max_moves
+        && pace.deadline.is_none_or(|deadline| Instant::now() < deadline)
+    {
+        if let Some(interval) = pace.interval {
+            thread::sleep(next_turn.saturating_duration_since(Instant::now()));
+            next_turn += interval;
+        }
+        let planned = strategy.next_move(&radar);
+        let Some(direction) = strategy::target_direction(&radar).or(planned) else {
+            report.waits += 1;
+            strategy.on_wait();
+            if pace.interval.is_none() {
+                thread::sleep(WAIT_DELAY);
+            }
+            continue;
+        };
+        report.moves += 1;
+        let result = {
+            // Le verrou est gardé du déplacement à la réponse : un coéquipier qui se déplacerait
+            // entre-temps renouvellerait son secret et fausserait la somme.
+            let mut hints = hints.lock().expect("indices empoisonnés");
+            perform_timed(&mut session, &mut hints, Action::MoveTo(direction.clone()), hook)
+        };
+        let events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                hook.on_failure(&e);
+                report.failure = Some(e.to_string());
+                break;
+            }
+        };
+        report.record(&events);
+        strategy::feed(strategy.as_mut(), &Action::MoveTo(direction.clone()), &events);
+        if let Some(next) = last_radar(&events) {
+            radar = next;
+        }
+        report.reached_target = strategy::reached_target(&radar);
+    }
+    report.elapsed = start.elapsed();
+    (session, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use server::challenge::ChallengeRules;
+    use server::game::{GameConfig, GameState};
+    use server::hints::HintRules;
+    use server::maze::generators::Algorithm;
+    use shared::messages::RelativeDirection;
+    use shared::radar::Passage;
+
+    #[test]
+    fn test_bots_reach_target_and_solve_challenges() {
+        let config = GameConfig {
+            players_per_team: 2,
+            seed: 3,
+            challenges: ChallengeRules { secret_sum_every: Some(4), sos_every: None },
+            ..GameConfig::default()
+        };
+        let maze = Algorithm::RecursiveBacktracker.generate(6, 5, 11);
+        let address = server::network::spawn("127.0.0.1:0", GameState::new(maze, config)).unwrap();
+
+        let reports = run(&BotConfig {
+            server_address: address.to_string(),
+            players: 2,
+            max_moves: 500,
+            ..BotConfig::default()
+        })
+        .unwrap();
+        assert_eq!(reports.len(), 2);
+        for report in reports {
+            assert!(report.reached_target, "{:?}", report);
+            assert!(report.failure.is_none());
+            assert!(!report.errors.contains_key("InvalidChallengeSolution"), "{:?}", report);
+        }
+    }
+
+    #[test]
+    fn test_frontier_bots_use_hints() {
+        let config = GameConfig {
+            players_per_team: 3,
+            hints: HintRules { compass_every: Some(3), grid_size_at_start: true, ..HintRules::default() },
+            ..GameConfig::default()
+        };
+        let maze = Algorithm::Braided.generate(10, 8, 5);
+        let address = server::network::spawn("127.0.0.1:0", GameState::new(maze, config)).unwrap();
+        let reports = run(&BotConfig {
+            server_address: address.to_string(),
+            strategy: "frontier".into(),
+            max_moves: 1000,
+            ..BotConfig::default()
+        })
+        .unwrap();
+        for report in reports {
+            assert!(report.reached_target, "{:?}", report);
+            assert!(report.errors.is_empty(), "{:?}", report);
+        }
+    }
+
+    #[test]
+    fn test_frontier_bots_get_past_monsters() {
+        let config = GameConfig { players_per_team: 3, monsters: 6, ..GameConfig::default() };
+        let maze = Algorithm::Braided.generate(10, 8, 5);
+        let address = server::network::spawn("127.0.0.1:0", GameState::new(maze, config)).unwrap();
+        let reports = run(&BotConfig {
+            server_address: address.to_string(),
+            strategy: "frontier".into(),
+            max_moves: 1000,
+            ..BotConfig::default()
+        })
+        .unwrap();
+        for report in reports {
+            assert!(report.reached_target, "{:?}", report);
+            assert!(report.errors.keys().all(|error| error == "CannotPassThroughOpponent"), "{:?}", report);
+        }
+    }
+
+    /// Tourne à chaque déplacement et note le cap de la sortie, mesuré depuis l'orientation initiale.
+    struct CompassRecorder {
+        quarter_turns: u32,
+        bearings: Arc<Mutex<Vec<f32>>>,
+    }
+
+    impl Strategy for CompassRecorder {
+        fn name(&self) -> &'static str {
+            "compass-recorder"
+        }
+
+        fn next_move(&mut self, radar: &RadarGrid) -> Option<RelativeDirection> {
+            [RelativeDirection::Left, RelativeDirection::Right, RelativeDirection::Back, RelativeDirection::Front]
+                .into_iter()
+                .find(|dir| radar.player_passage(dir.clone()) == Passage::Open)
+        }
+
+        fn on_accepted(&mut self, direction: &RelativeDirection) {
+            self.quarter_turns += match direction {
+                RelativeDirection::Front => 0,
+                RelativeDirection::Right => 1,
+                RelativeDirection::Back => 2,
+                RelativeDirection::Left => 3,
+            };
+        }
+
+        fn on_hint(&mut self, hint: &Hint) {
+            if let Hint::RelativeCompass { angle } = hint {
+                let bearing = (self.quarter_turns % 4) as f32 * 90.0 + angle;
+                self.bearings.lock().unwrap().push(bearing % 360.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compass_follows_the_move_that_precedes_it() {
+        // La sortie, murée, est à l'est d'un couloir où le joueur fait demi-tour à chaque pas :
+        // vue depuis l'orientation initiale, elle doit toujours être dans la même direction.
+        let corridor = server::maze::Maze::from_ascii(
+            "
+            +-+-+-+-+-+
+            |       |E|
+            +-+-+-+-+-+
+            ",
+        );
+        let config = GameConfig {
+            hints: HintRules { compass_every: Some(1), ..HintRules::default() },
+            ..GameConfig::default()
+        };
+        let address = server::network::spawn("127.0.0.1:0", GameState::new(corridor, config)).unwrap();
+        let config = BotConfig { server_address: address.to_string(), players: 1, ..BotConfig::default() };
+        let (hints, mut players) = subscribe_team(&config, &mut ()).unwrap();
+
+        let bearings = Arc::new(Mutex::new(Vec::new()));
+        let strategy = CompassRecorder { quarter_turns: 0, bearings: bearings.clone() };
+        let hints = Mutex::new(hints);
+        let (session, report) = play(players.remove(0), Box::new(strategy), &hints, 8, Pace::default(), &mut ());
+        session.shutdown().unwrap();
+        assert_eq!(report.moves, 8);
+        let bearings = bearings.lock().unwrap();
+        assert_eq!(bearings.len(), 8);
+        assert!(bearings.iter().all(|bearing| (bearing - bearings[0]).abs() < 1.0), "{:?}", bearings);
+    }
+
+    #[test]
+    fn test_unknown_strategy() {
+        let config = BotConfig { strategy: "teleport".into(), ..BotConfig::default() };
+        assert!(matches!(run(&config), Err(BotError::UnknownStrategy(_))));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::bot::{self, RequestHook};
+use crate::error::ClientError;
+use crate::hints::HintBook;
+use crate::session::{PlayerSession, SessionEvent};
+use shared::messages::Action;
+
+/// Joue une action sur la session en tenant les indices de l'équipe à jour,
+/// et répond aussitôt à un `SecretSumModulo` lancé par le serveur.
+/// Les événements de la réponse au challenge suivent ceux de l'action.
+pub fn perform_solving(
+    session: &mut PlayerSession,
+    hints: &mut HintBook,
+    action: Action,
+) -> Result<Vec<SessionEvent>, ClientError> {
+    perform_timed(session, hints, action, &mut ())
+}
+
+/// Comme [`perform_solving`], en transmettant à `hook` la durée de chaque requête.
+pub(crate) fn perform_timed(
+    session: &mut PlayerSession,
+    hints: &mut HintBook,
+    action: Action,
+    hook: &mut impl RequestHook,
+) -> Result<Vec<SessionEvent>, ClientError> {
+    let kind = match action {
+        Action::MoveTo(_) => "MoveTo",
+        Action::SolveChallenge { .. } => "SolveChallenge",
+    };
+    let mut events = bot::timed(hook, kind, || session.perform(action))?;
+    hints.observe(&session.player_name, &events);
+    let answer = events.iter().find_map(|event| match event {
+        SessionEvent::Challenge(challenge) => hints.answer(challenge),
+        _ => None,
+    });
+    if let Some(answer) = answer {
+        let solved = bot::timed(hook, "SolveChallenge", || session.perform(Action::SolveChallenge { answer }))?;
+        hints.observe(&session.player_name, &solved);
+        events.extend(solved);
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameClient;
+    use server::challenge::ChallengeRules;
+    use server::game::{GameConfig, GameState};
+    use server::maze::Maze;
+    use shared::messages::{ActionError, RelativeDirection};
+
+    #[test]
+    fn test_team_solves_challenges_against_local_server() {
+        let config = GameConfig {
+            players_per_team: 2,
+            challenges: ChallengeRules { secret_sum_every: Some(1), sos_every: None },
+            ..GameConfig::default()
+        };
+        let address = server::network::spawn("127.0.0.1:0", GameState::new(Maze::open(3, 3), config))
+            .unwrap()
+            .to_string();
+        let mut client = GameClient::new(&address).unwrap();
+        let token = client.register_team("alpha").unwrap().registration_token;
+
+        let mut hints = HintBook::default();
+        let mut sessions = Vec::new();
+        for name in ["alice", "bob"] {
+            let (session, _, events) = PlayerSession::subscribe(&address, &token, name).unwrap();
+            hints.observe(name, &events);
+            sessions.push(session);
+        }
+
+        // Chaque déplacement réussi lance un challenge, qui doit être résolu pour continuer.
+        let mut solved = 0;
+        let directions = [RelativeDirection::Front, RelativeDirection::Right, RelativeDirection::Back];
+        for direction in directions.iter().cycle().take(9) {
+            let events = perform_solving(&mut sessions[0], &mut hints, Action::MoveTo(direction.clone())).unwrap();
+            assert!(!events.contains(&SessionEvent::ActionError(ActionError::InvalidChallengeSolution)), "{:?}", events);
+            assert!(!events.contains(&SessionEvent::ActionError(ActionError::SolveChallengeFirst)), "{:?}", events);
+            if events.iter().any(|e| matches!(e, SessionEvent::Challenge(_))) {
+                assert!(matches!(events.last(), Some(SessionEvent::RadarView(_))));
+                solved += 1;
+            }
+        }
+        assert!(solved > 0);
+        for session in sessions {
+            session.shutdown().unwrap();
+        }
+    }
+}
@@ -0,0 +1,450 @@
+use std::time::{Duration, Instant};
+
+use crate::bot::{PlayerReport, Subscribed};
+use crate::challenge::perform_solving;
+use crate::hints::HintBook;
+use crate::maze_solver::{self, FrontierPlanner};
+use crate::session::{last_radar, move_outcome, PlayerSession, SessionEvent};
+use crate::strategy;
+use crate::world_map::{Direction, Frame, MapPos, WorldMap};
+use shared::messages::{Action, ActionError, Challenge, Hint, RelativeDirection};
+use shared::radar::RadarGrid;
+
+/// Passages communs exigés pour recaler la carte d'un joueur sur la carte partagée.
+const MIN_AGREEMENT: usize = 40;
+/// Nombre de tours entre deux tentatives de recalage des joueurs.
+const ALIGN_EVERY: u32 = 3;
+/// Un joueur en SOS retente un déplacement tous les `PROBE_EVERY` tours,
+/// au cas où un coéquipier l'aurait rejoint sans que le coordinateur puisse le suivre.
+const PROBE_EVERY: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Exploring,
+    /// Bloqué par un challenge `SOS` depuis le tour indiqué, attend un coéquipier.
+    InSos { since: u32 },
+    /// Rejoint le coéquipier d'indice donné, en SOS.
+    Helping(usize),
+    Escaped,
+}
+
+/// Joueur piloté par le coordinateur.
+struct Member {
+    session: PlayerSession,
+    radar: RadarGrid,
+    /// Carte du joueur dans son propre repère, tenue à jour même après le recalage.
+    local: WorldMap,
+    /// Passage du repère du joueur à celui de la carte partagée, une fois les cartes recalées.
+    frame: Option<Frame>,
+    planner: FrontierPlanner,
+    /// Dernière boussole reçue et orientation du joueur dans son repère à ce moment-là.
+    compass: Option<(f32, Direction)>,
+    role: Role,
+    /// A reçu un `Hint::SOSHelper` qui n'a pas encore servi.
+    called: bool,
+    report: PlayerReport,
+    /// Session perdue ou déplacements épuisés.
+    done: bool,
+}
+
+impl Member {
+    /// Position et orientation dans le repère de la carte partagée.
+    fn pose(&self) -> Option<(MapPos, Direction)> {
+        self.frame.map(|frame| (frame.position(self.local.position()), frame.direction(self.local.facing())))
+    }
+
+    fn finished(&self) -> bool {
+        self.done || self.role == Role::Escaped
+    }
+
+    /// Rôle à reprendre une fois un SOS ou un sauvetage terminé.
+    fn idle_role(&self) -> Role {
+        if self.report.reached_target { Role::Escaped } else { Role::Exploring }
+    }
+}
+
+/// Fait jouer toute une équipe dans un même thread, un déplacement par joueur et par tour.
+///
+/// Le repère du premier joueur sert de carte partagée ; la carte de chaque autre joueur y est
+/// recalée dès qu'une superposition concorde nettement mieux que toutes les autres. Les joueurs recalés
+/// explorent alors des frontières différentes. Les secrets de tous alimentent un même
+/// [`HintBook`] pour répondre aux `SecretSumModulo`, et un joueur qui reçoit `Hint::SOSHelper`
+/// part rejoindre le coéquipier en SOS.
+pub struct Coordinator {
+    members: Vec<Member>,
+    shared: WorldMap,
+    hints: HintBook,
+    round: u32,
+    start: Instant,
+}
+
+impl Coordinator {
+    pub fn new(hints: HintBook, players: Vec<Subscribed>) -> Self {
+        let members = players.into_iter()
+            .map(|(session, radar, _)| {
+                let mut local = WorldMap::new();
+                local.observe(&radar);
+                let report = PlayerReport::new(session.player_name.clone(), strategy::reached_target(&radar));
+                let mut member = Member {
+                    session,
+                    radar,
+                    local,
+                    frame: None,
+                    planner: FrontierPlanner::new(),
+                    compass: None,
+                    role: Role::Exploring,
+                    called: false,
+                    report,
+                    done: false,
+                };
+                member.role = member.idle_role();
+                member
+            })
+            .collect();
+        let mut coordinator = Self { members, shared: WorldMap::new(), hints, round: 0, start: Instant::now() };
+        if let Some((columns, rows)) = coordinator.hints.grid_size() {
+            coordinator.shared.set_grid_size(columns, rows);
+            for member in &mut coordinator.members {
+                member.local.set_grid_size(columns, rows);
+            }
+        }
+        if let Some(first) = coordinator.members.first_mut() {
+            first.frame = Some(Frame::IDENTITY);
+            coordinator.shared.merge(&first.local, Frame::IDENTITY);
+        }
+        coordinator
+    }
+
+    /// Carte commune aux joueurs recalés, dans le repère du premier joueur.
+    pub fn shared_map(&self) -> &WorldMap {
+        &self.shared
+    }
+
+    /// Nombre de joueurs dont la carte a été recalée sur la carte partagée.
+    pub fn aligned_players(&self) -> usize {
+        self.members.iter().filter(|m| m.frame.is_some()).count()
+    }
+
+    /// Joue jusqu'à ce que tous les joueurs soient sortis ou aient épuisé leurs `max_moves` déplacements.
+    pub fn run(mut self, max_moves: u32) -> Vec<PlayerReport> {
+        while self.play_round(max_moves) {}
+        let elapsed = self.start.elapsed();
+        self.members.into_iter()
+            .map(|member| {
+                // Fermeture au mieux : la partie est finie, le serveur a pu couper avant nous.
+                member.session.shutdown().ok();
+                let mut report = member.report;
+                if report.elapsed == Duration::ZERO {
+                    report.elapsed = elapsed;
+                }
+                report
+            })
+            .collect()
+    }
+
+    /// Fait jouer un tour. Renvoie `false` quand tous les joueurs sont sortis ou à court de déplacements.
+    pub fn play_round(&mut self, max_moves: u32) -> bool {
+        self.round += 1;
+        self.shared.tick();
+        for member in &mut self.members {
+            member.local.tick();
+        }
+        if self.round.is_multiple_of(ALIGN_EVERY) {
+            self.align_members();
+        }
+        self.assign_helpers();
+        for i in 0..self.members.len() {
+            let member = &mut self.members[i];
+            if member.report.moves + member.report.waits >= max_moves {
+                member.done = true;
+            }
+            if member.done || (member.finished() && !matches!(member.role, Role::Helping(_))) {
+                continue;
+            }
+            match self.decide(i) {
+                Some(direction) => self.perform(i, direction),
+                None if matches!(self.members[i].role, Role::Exploring | Role::Helping(_)) => {
+                    self.members[i].report.waits += 1;
+                }
+                None => {}
+            }
+        }
+        self.members.iter().any(|m| !m.done && !m.report.reached_target)
+    }
+
+    fn align_members(&mut self) {
+        for member in self.members.iter_mut().filter(|m| m.frame.is_none() && !m.done) {
+            let Some(frame) = self.shared.align(&member.local, MIN_AGREEMENT) else {
+                continue;
+            };
+            self.shared.merge(&member.local, frame);
+            member.frame = Some(frame);
+            member.planner = FrontierPlanner::new();
+            if let Some((angle, facing)) = member.compass {
+                member.planner.observe_compass(angle, frame.direction(facing));
+            }
+        }
+    }
+
+    /// Envoie un coéquipier vers chaque joueur en SOS qui n'a pas encore de sauveteur : un joueur
+    /// qui a reçu `Hint::SOSHelper`, ou à défaut un joueur déjà sorti, qui n'interroge plus le
+    /// serveur et ne peut donc pas recevoir l'indice.
+    fn assign_helpers(&mut self) {
+        for j in 0..self.members.len() {
+            let waiting = matches!(self.members[j].role, Role::InSos { .. })
+                && !self.members.iter().any(|m| m.role == Role::Helping(j));
+            if !waiting {
+                continue;
+            }
+            let helper = self.members.iter()
+                .position(|m| !m.done && m.called && m.role == Role::Exploring)
+                .or_else(|| self.members.iter().position(|m| !m.done && m.role == Role::Escaped));
+            if let Some(k) = helper {
+                self.members[k].role = Role::Helping(j);
+                self.members[k].called = false;
+            }
+        }
+    }
+
+    /// Déplacement du joueur `i` pour ce tour, `None` s'il attend.
+    fn decide(&mut self, i: usize) -> Option<RelativeDirection> {
+        match self.members[i].role {
+            Role::Escaped => None,
+            Role::InSos { since } if !(self.round - since).is_multiple_of(PROBE_EVERY) => None,
+            Role::Helping(j) => {
+                let goal = self.members[j].pose().map(|(pos, _)| pos);
+                match (self.members[i].pose(), goal) {
+                    (Some((pos, facing)), Some(goal)) => {
+                        self.shared.place(pos, facing);
+                        maze_solver::step_towards(&self.shared, goal).or_else(|| self.explore(i))
+                    }
+                    _ => self.explore(i),
+                }
+            }
+            Role::Exploring | Role::InSos { .. } => {
+                let planned = self.explore(i);
+                strategy::target_direction(&self.members[i].radar).or(planned)
+            }
+        }
+    }
+
+    /// Prochain déplacement d'exploration : sur la carte partagée, en laissant aux coéquipiers
+    /// les frontières qu'ils visent, ou sur la carte du joueur tant qu'il n'est pas recalé.
+    /// `None` si le joueur doit attendre qu'une menace s'écarte.
+    fn explore(&mut self, i: usize) -> Option<RelativeDirection> {
+        let claimed: Vec<MapPos> = self.members.iter().enumerate()
+            .filter(|(j, m)| *j != i && m.frame.is_some() && !m.finished())
+            .filter_map(|(_, m)| m.planner.goal())
+            .collect();
+        let member = &mut self.members[i];
+        member.planner.set_ignore_target(member.report.reached_target);
+        match member.pose() {
+            Some((pos, facing)) => {
+                self.shared.place(pos, facing);
+                member.planner.set_claimed(claimed);
+                member.planner.next_move(&self.shared)
+            }
+            None => member.planner.next_move(&member.local),
+        }
+    }
+
+    fn perform(&mut self, i: usize, direction: RelativeDirection) {
+        let round = self.round;
+        let member = &mut self.members[i];
+        let result = perform_solving(&mut member.session, &mut self.hints, Action::MoveTo(direction.clone()));
+        member.report.moves += 1;
+        let events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                member.report.failure = Some(e.to_string());
+                member.done = true;
+                return;
+            }
+        };
+        match move_outcome(&events) {
+            Some(Ok(())) => {
+                member.local.apply_move(&direction);
+                if matches!(member.role, Role::InSos { .. }) {
+                    member.role = member.idle_role();
+                }
+            }
+            Some(Err(ActionError::CannotPassThroughOpponent)) => {
+                member.local.mark_blocked(&direction);
+                if let Some((pos, facing)) = member.pose() {
+                    self.shared.place(pos, facing);
+                    self.shared.mark_blocked(&direction);
+                }
+            }
+            _ => {}
+        }
+        if let Some(radar) = last_radar(&events) {
+            member.radar = radar;
+            member.local.observe(&radar);
+            if let Some((pos, facing)) = member.pose() {
+                self.shared.place(pos, facing);
+                self.shared.observe(&radar);
+            }
+        }
+        if strategy::reached_target(&member.radar) && !member.report.reached_target {
+            member.report.reached_target = true;
+            member.report.elapsed = self.start.elapsed();
+            if member.role == Role::Exploring {
+                member.role = Role::Escaped;
+            }
+        }
+        self.members[i].report.record(&events);
+        for event in &events {
+            match event {
+                SessionEvent::Challenge(Challenge::SOS) => self.members[i].role = Role::InSos { since: round },
+                SessionEvent::Hint(hint) => self.on_hint(i, hint),
+                _ => {}
+            }
+        }
+        self.check_rescue(i);
+    }
+
+    fn on_hint(&mut self, i: usize, hint: &Hint) {
+        match hint {
+            Hint::GridSize { columns, rows } => {
+                self.members[i].local.set_grid_size(*columns, *rows);
+                self.shared.set_grid_size(*columns, *rows);
+            }
+            Hint::RelativeCompass { angle } => {
+                let member = &mut self.members[i];
+                let facing = member.local.facing();
+                member.compass = Some((*angle, facing));
+                let map_facing = member.frame.map_or(facing, |frame| frame.direction(facing));
+                member.planner.observe_compass(*angle, map_facing);
+            }
+            Hint::SOSHelper => self.members[i].called = true,
+            Hint::Secret(_) => {}
+        }
+    }
+
+    /// Le serveur libère un joueur en SOS dès qu'un coéquipier arrive sur sa cellule.
+    fn check_rescue(&mut self, i: usize) {
+        let Role::Helping(j) = self.members[i].role else {
+            return;
+        };
+        let rescued = match (self.members[i].pose(), self.members[j].pose()) {
+            (Some((helper, _)), Some((waiting, _))) => helper == waiting,
+            _ => false,
+        };
+        if rescued || !matches!(self.members[j].role, Role::InSos { .. }) {
+            if rescued {
+                self.members[j].role = self.members[j].idle_role();
+                self.members[i].report.rescues += 1;
+            }
+            self.members[i].role = self.members[i].idle_role();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::{self, BotConfig};
+    use server::challenge::ChallengeRules;
+    use server::game::{GameConfig, GameState};
+    use server::maze::generators::Algorithm;
+    use server::maze::{Direction as ServerDirection, Maze};
+    use server::radar::{compute_radar, Entities};
+
+    #[test]
+    fn test_team_explores_together_and_answers_sos() {
+        let (mut sos, mut helper_calls, mut rescues) = (0, 0, 0);
+        // Un labyrinthe par algorithme de génération ; les SOS tombent tous les 12 déplacements.
+        for (seed, algorithm) in Algorithm::ALL.into_iter().enumerate() {
+            let config = GameConfig {
+                players_per_team: 3,
+                seed: seed as u64,
+                challenges: ChallengeRules { secret_sum_every: Some(5), sos_every: Some(12) },
+                ..GameConfig::default()
+            };
+            let maze = algorithm.generate(8, 6, seed as u64);
+            let address = server::network::spawn("127.0.0.1:0", GameState::new(maze, config)).unwrap();
+
+            let reports = bot::run(&BotConfig {
+                server_address: address.to_string(),
+                coordinated: true,
+                max_moves: 500,
+                ..BotConfig::default()
+            })
+            .unwrap();
+            assert_eq!(reports.len(), 3);
+            for report in &reports {
+                assert!(report.reached_target, "{:?}", report);
+                assert!(report.failure.is_none());
+                assert!(!report.errors.contains_key("InvalidChallengeSolution"), "{:?}", report);
+            }
+            let total = |count: fn(&PlayerReport) -> u32| reports.iter().map(count).sum::<u32>();
+            assert!(total(|r| r.sos) > 0, "{:?}", reports);
+            assert!(total(|r| r.rescues) <= total(|r| r.sos), "{:?}", reports);
+            sos += total(|r| r.sos);
+            helper_calls += total(|r| r.helper_calls);
+            rescues += total(|r| r.rescues);
+        }
+        assert!(helper_calls > 0 && rescues > 0, "{} SOS, {} appels, {} sauvetages", sos, helper_calls, rescues);
+    }
+
+    /// Coordinateur de deux joueurs inscrits sur un serveur local, placés à la même position
+    /// d'un couloir de 9 cases dont ils connaissent les 5 du milieu, leurs cartes recalées.
+    fn coordinator_in_corridor() -> Coordinator {
+        let corridor = Maze::from_ascii(
+            "
+            +-+-+-+-+-+-+-+-+-+
+            |                E|
+            +-+-+-+-+-+-+-+-+-+
+            ",
+        );
+        let config = GameConfig { players_per_team: 2, ..GameConfig::default() };
+        let address = server::network::spawn("127.0.0.1:0", GameState::new(corridor.clone(), config)).unwrap();
+        let config = BotConfig { server_address: address.to_string(), players: 2, ..BotConfig::default() };
+        let (hints, players) = bot::subscribe_team(&config, &mut ()).unwrap();
+
+        let mut coordinator = Coordinator::new(hints, players);
+        let mut local = WorldMap::new();
+        for column in 2..=6 {
+            if column > 2 {
+                local.apply_move(&RelativeDirection::Front);
+            }
+            local.observe(&compute_radar(&corridor, (0, column), ServerDirection::East, &Entities::default()));
+        }
+        coordinator.shared = WorldMap::new();
+        coordinator.shared.merge(&local, Frame::IDENTITY);
+        for member in &mut coordinator.members {
+            member.local = local.clone();
+            member.frame = Some(Frame::IDENTITY);
+            member.role = Role::Exploring;
+        }
+        coordinator
+    }
+
+    #[test]
+    fn test_claims_send_explorers_to_different_frontiers() {
+        let mut coordinator = coordinator_in_corridor();
+        assert_eq!(coordinator.explore(0), Some(RelativeDirection::Front));
+        assert_eq!(coordinator.explore(1), Some(RelativeDirection::Back));
+        let goals: Vec<MapPos> = coordinator.members.iter().filter_map(|m| m.planner.goal()).collect();
+        assert_eq!(goals.len(), 2);
+        assert!((goals[0].0 - goals[1].0).abs() + (goals[0].1 - goals[1].1).abs() > 2, "{:?}", goals);
+    }
+
+    #[test]
+    fn test_sos_helper_is_sent_to_teammate() {
+        let mut coordinator = coordinator_in_corridor();
+        coordinator.members[1].role = Role::InSos { since: 0 };
+        coordinator.assign_helpers();
+        // Sans appel du serveur ni joueur sorti, personne n'est envoyé.
+        assert_eq!(coordinator.members[0].role, Role::Exploring);
+        coordinator.on_hint(0, &Hint::SOSHelper);
+        coordinator.assign_helpers();
+        assert_eq!(coordinator.members[0].role, Role::Helping(1));
+        assert!(!coordinator.members[0].called);
+        // Les deux joueurs sont sur la même cellule : le coéquipier est libéré.
+        coordinator.check_rescue(0);
+        assert_eq!(coordinator.members[1].role, Role::Exploring);
+        assert_eq!(coordinator.members[0].role, Role::Exploring);
+        assert_eq!(coordinator.members[0].report.rescues, 1);
+    }
+}
@@ -0,0 +1,71 @@
+use std::fmt;
+use std::io;
+
+use shared::messages::Message;
+use shared::radar::RadarError;
+use shared::transport::TransportError;
+
+/// Erreurs des opérations du client : connexion, échanges avec le serveur et refus du serveur.
+#[derive(Debug)]
+pub enum ClientError {
+    /// Le serveur n'a pas pu être joint.
+    Connect { address: String, source: io::Error },
+    /// Erreur d'entrée/sortie sur une connexion établie.
+    Io(io::Error),
+    /// Connexion fermée par le serveur ou trame hors des limites.
+    Framing(TransportError),
+    /// Le corps d'une trame n'est pas un message JSON valide.
+    Json(serde_json::Error),
+    /// `RegisterTeamResult::Err` : le serveur a refusé l'inscription de l'équipe.
+    RegisterTeam(String),
+    /// `SubscribePlayerResult::Err` : le serveur a refusé l'inscription du joueur.
+    SubscribePlayer(String),
+    /// Le serveur a envoyé un message qui n'a pas de sens à ce moment.
+    Unexpected(Message),
+    /// Une `RadarView` reçue ne se décode pas.
+    InvalidRadar(RadarError),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Connect { address, source } => write!(f, "connexion à {} impossible: {}", address, source),
+            ClientError::Io(e) => write!(f, "erreur d'entrée/sortie: {}", e),
+            ClientError::Framing(e) => write!(f, "{}", e),
+            ClientError::Json(e) => write!(f, "message JSON invalide: {}", e),
+            ClientError::RegisterTeam(reason) => write!(f, "inscription de l'équipe refusée: {}", reason),
+            ClientError::SubscribePlayer(reason) => write!(f, "inscription du joueur refusée: {}", reason),
+            ClientError::Unexpected(msg) => write!(f, "message inattendu: {:?}", msg),
+            ClientError::InvalidRadar(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClientError::Connect { source, .. } => Some(source),
+            ClientError::Io(e) => Some(e),
+            ClientError::Framing(e) => Some(e),
+            ClientError::Json(e) => Some(e),
+            ClientError::InvalidRadar(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(e: io::Error) -> Self {
+        ClientError::Io(e)
+    }
+}
+
+impl From<TransportError> for ClientError {
+    fn from(e: TransportError) -> Self {
+        match e {
+            TransportError::Io(e) => ClientError::Io(e),
+            TransportError::Json(e) => ClientError::Json(e),
+            other => ClientError::Framing(other),
+        }
+    }
+}
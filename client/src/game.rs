@@ -1,182 +1,73 @@
-use std::io::{Read, Write};
-use std::net::TcpStream;
-use serde_json;
+use crate::error::ClientError;
+use crate::network::{self, Connection};
 use shared::messages::{
-    RegisterTeamResultWrapper,
+    Message,
+    RegisterTeam,
     RegisterTeamResult,
-    RelativeDirection,
+    RegisterTeamResultOk,
 };
 
 /// Gère la connexion et les actions liées à l’équipe.
 pub struct GameClient {
-    pub stream: TcpStream,
+    pub stream: Connection,
     pub registration_token: Option<String>,
-    pub server_address: String,
 }
 
 impl GameClient {
-    pub fn new(server_address: &str) -> Self {
-        match TcpStream::connect(server_address) {
-            Ok(stream) => {
-                println!("✅ Connecté au serveur sur {}", server_address);
-                GameClient {
-                    stream,
-                    registration_token: None,
-                    server_address: server_address.to_string(),
-                }
-            }
-            Err(e) => {
-                eprintln!("❌ Erreur de connexion au serveur: {}", e);
-                std::process::exit(1);
-            }
-        }
-    }
-
-    pub fn register_team(&mut self, team_name: &str) {
-        let msg = serde_json::json!({
-            "RegisterTeam": { "name": team_name }
-        }).to_string();
-        let size = (msg.len() as u32).to_le_bytes();
-
-        println!("📤 Envoi RegisterTeam ({} octets)", msg.len());
-        if self.stream.write_all(&size).is_err() {
-            eprintln!("❌ Erreur d'envoi taille RegisterTeam");
-            return;
-        }
-        if self.stream.write_all(msg.as_bytes()).is_err() {
-            eprintln!("❌ Erreur d'envoi message RegisterTeam");
-            return;
-        }
-
-        let mut size_buf = [0; 4];
-        if self.stream.read_exact(&mut size_buf).is_err() {
-            eprintln!("❌ Erreur lecture taille réponse RegisterTeam");
-            return;
-        }
-        let resp_size = u32::from_le_bytes(size_buf);
-        let mut buffer = vec![0; resp_size as usize];
-        if self.stream.read_exact(&mut buffer).is_err() {
-            eprintln!("❌ Erreur lecture message RegisterTeam");
-            return;
-        }
-        let resp = String::from_utf8_lossy(&buffer).to_string();
-        println!("📩 Réponse RegisterTeam: {}", resp);
-
-        let parsed = serde_json::from_str::<RegisterTeamResultWrapper>(&resp);
-        if let Ok(wrapper) = parsed {
-            match wrapper.register_team_result {
-                RegisterTeamResult::OkVariant { ok } => {
-                    println!("✅ RegisterTeam OK, token={}", ok.registration_token);
-                    self.registration_token = Some(ok.registration_token);
-                }
-                RegisterTeamResult::ErrVariant { err } => {
-                    eprintln!("❌ RegisterTeam err: {}", err);
-                }
-            }
-        } else {
-            eprintln!("❌ Erreur de parsing RegisterTeam");
-        }
+    pub fn new(server_address: &str) -> Result<Self, ClientError> {
+        Ok(GameClient {
+            stream: network::connect(server_address)?,
+            registration_token: None,
+        })
     }
 
-    pub fn subscribe_player(&self, player_name: &str) {
-        if let Some(tok) = &self.registration_token {
-            if let Ok(mut s) = TcpStream::connect(&self.server_address) {
-                let msg = serde_json::json!({
-                    "SubscribePlayer": {
-                        "name": player_name,
-                        "registration_token": tok
-                    }
-                }).to_string();
-                let size = (msg.len() as u32).to_le_bytes();
-
-                println!("📤 SubscribePlayer -> {}", msg);
-                if s.write_all(&size).is_err() {
-                    eprintln!("❌ Erreur envoi taille SubscribePlayer");
-                    return;
-                }
-                if s.write_all(msg.as_bytes()).is_err() {
-                    eprintln!("❌ Erreur envoi message SubscribePlayer");
-                    return;
-                }
-                let mut sz_buf = [0; 4];
-                if s.read_exact(&mut sz_buf).is_err() {
-                    eprintln!("❌ Erreur lecture taille réponse SubscribePlayer");
-                    return;
-                }
-                let rsize = u32::from_le_bytes(sz_buf);
-                let mut buffer = vec![0; rsize as usize];
-                if s.read_exact(&mut buffer).is_err() {
-                    eprintln!("❌ Erreur lecture message SubscribePlayer");
-                    return;
-                }
-                let resp = String::from_utf8_lossy(&buffer).to_string();
-                println!("📩 Réponse SubscribePlayer: {}", resp);
-            } else {
-                eprintln!("❌ Impossible de se connecter pour SubscribePlayer");
+    /// Inscrit l'équipe et garde son token d'inscription.
+    pub fn register_team(&mut self, team_name: &str) -> Result<RegisterTeamResultOk, ClientError> {
+        self.stream.send(&Message::RegisterTeam(RegisterTeam {
+            name: team_name.to_string(),
+        }))?;
+        match self.stream.recv()? {
+            Message::RegisterTeamResult(RegisterTeamResult::Ok(ok)) => {
+                self.registration_token = Some(ok.registration_token.clone());
+                Ok(ok)
             }
-        } else {
-            eprintln!("❌ Aucun token (register_team) pour subscribe_player");
+            Message::RegisterTeamResult(RegisterTeamResult::Err(err)) => Err(ClientError::RegisterTeam(err)),
+            other => Err(ClientError::Unexpected(other)),
         }
     }
+}
 
-    pub fn join_game(server_address: &str, token: &str, player_name: &str) {
-        if let Ok(mut s) = TcpStream::connect(server_address) {
-            let msg = serde_json::json!({
-                "SubscribePlayer": {
-                    "name": player_name,
-                    "registration_token": token
-                }
-            }).to_string();
-            let size = (msg.len() as u32).to_le_bytes();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use server::game::{GameConfig, GameState};
+    use server::maze::Maze;
 
-            println!("📤 JoinGame Subscribe -> {}", msg);
-            if s.write_all(&size).is_err() {
-                eprintln!("❌ Erreur envoi taille JoinGame");
-                return;
-            }
-            if s.write_all(msg.as_bytes()).is_err() {
-                eprintln!("❌ Erreur envoi message JoinGame");
-                return;
-            }
-            let mut sz_buf = [0; 4];
-            if s.read_exact(&mut sz_buf).is_err() {
-                eprintln!("❌ Erreur lecture taille réponse JoinGame");
-                return;
-            }
-            let rsize = u32::from_le_bytes(sz_buf);
-            let mut buffer = vec![0; rsize as usize];
-            if s.read_exact(&mut buffer).is_err() {
-                eprintln!("❌ Erreur lecture message JoinGame");
-                return;
-            }
-            let resp = String::from_utf8_lossy(&buffer).to_string();
-            println!("📩 Réponse JoinGame: {}", resp);
-        } else {
-            eprintln!("❌ Impossible de connecter pour JoinGame");
+    #[test]
+    fn test_connect_refused() {
+        // Un port libéré aussitôt réservé : personne n'y écoute plus.
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+        match GameClient::new(&address) {
+            Err(ClientError::Connect { address: failed, .. }) => assert_eq!(failed, address),
+            other => panic!("connexion inattendue: {:?}", other.map(|_| ())),
         }
     }
 
-    /// Envoie une action MoveTo sur un nouveau flux.
-    pub fn send_move_action_static(server_address: &str, dir: RelativeDirection) {
-        if let Ok(mut s) = TcpStream::connect(server_address) {
-            let msg = serde_json::json!({
-                "Action": {
-                    "MoveTo": format!("{:?}", dir)
-                }
-            }).to_string();
-            let size = (msg.len() as u32).to_le_bytes();
+    #[test]
+    fn test_register_team_twice_is_rejected() {
+        let address = server::network::spawn("127.0.0.1:0", GameState::new(Maze::open(3, 3), GameConfig::default()))
+            .unwrap()
+            .to_string();
+        let mut client = GameClient::new(&address).unwrap();
+        let ok = client.register_team("alpha").unwrap();
+        assert_eq!(client.registration_token, Some(ok.registration_token));
 
-            println!("📤 MoveTo: {}", msg);
-            if s.write_all(&size).is_err() {
-                eprintln!("❌ Erreur envoi taille MoveTo");
-                return;
-            }
-            if s.write_all(msg.as_bytes()).is_err() {
-                eprintln!("❌ Erreur envoi message MoveTo");
-                return;
-            }
-        } else {
-            eprintln!("❌ Impossible de se connecter pour MoveTo");
+        let mut other = GameClient::new(&address).unwrap();
+        match other.register_team("alpha") {
+            Err(ClientError::RegisterTeam(reason)) => assert_eq!(reason, "AlreadyRegistered"),
+            result => panic!("inscription inattendue: {:?}", result),
         }
+        assert_eq!(other.registration_token, None);
     }
 }
@@ -1,71 +1,174 @@
-use eframe::{egui, App, Frame};
-use rand::Rng; // Ajoutez la dépendance `rand` dans votre Cargo.toml pour ce module
+use eframe::egui;
+use rand::Rng;
+use client::hints::HintBook;
+use shared::radar::{Passage, RadarError, RadarGrid, RadarItem};
+use std::time::Duration;
 
-/// Vue du jeu qui simule dynamiquement le RadarView du labyrinthe.
-/// Affiche une grille 7x7 avec la cellule centrale en vert.
-/// La grille est mise à jour toutes les 2 secondes pour simuler des changements.
+/// Vue du jeu qui affiche la dernière RadarView reçue du serveur.
+/// Les 3x3 cellules sont dessinées avec leurs passages : murs en trait épais,
+/// passages indéfinis en pointillés, passages ouverts sans trait.
+/// Le mode démo remplace la vue serveur par une vue aléatoire renouvelée toutes les 2 secondes.
 pub struct GameView {
-    pub grid: Vec<Vec<bool>>,
-    pub player_pos: (usize, usize),
+    pub radar: Option<RadarGrid>,
+    pub demo_mode: bool,
     pub last_update: f64,
 }
 
 impl Default for GameView {
     fn default() -> Self {
-        // Initialisation d'une grille statique
-        let grid = vec![
-            vec![true, true, true, true, true, true, true],
-            vec![true, false, false, false, false, false, true],
-            vec![true, false, true, false, true, false, true],
-            vec![true, false, false, false, false, false, true],
-            vec![true, false, true, false, true, false, true],
-            vec![true, false, false, false, false, false, true],
-            vec![true, true, true, true, true, true, true],
-        ];
         Self {
-            grid,
-            player_pos: (3, 3),
+            radar: None,
+            demo_mode: false,
             last_update: 0.0,
         }
     }
 }
 
-impl App for GameView {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
-        // Simulation d'une mise à jour dynamique toutes les 2 secondes
-        let time = ctx.input(|i| i.time);
-        if time - self.last_update > 2.0 {
-            self.last_update = time;
-            // Mise à jour aléatoire de la grille pour simuler une nouvelle vue Radar
-            let mut rng = rand::thread_rng();
-            for row in self.grid.iter_mut() {
-                for cell in row.iter_mut() {
-                    *cell = rng.gen_bool(0.5);
-                }
+impl GameView {
+    /// Décode et mémorise une RadarView reçue du serveur.
+    pub fn set_radar(&mut self, encoded: &str) -> Result<(), RadarError> {
+        self.radar = Some(RadarGrid::decode(encoded)?);
+        Ok(())
+    }
+
+    /// Simulation d'une vue radar, utilisée uniquement en mode démo.
+    fn random_radar() -> RadarGrid {
+        let mut rng = rand::thread_rng();
+        let passages = [Passage::Undefined, Passage::Open, Passage::Wall, Passage::Open];
+        let items = [RadarItem::None, RadarItem::None, RadarItem::None, RadarItem::Ally,
+            RadarItem::Enemy, RadarItem::Monster, RadarItem::Hint, RadarItem::Target];
+        let mut grid = RadarGrid::default();
+        for row in 0..4 {
+            for col in 0..3 {
+                grid.set_horizontal(row, col, passages[rng.gen_range(0..passages.len())]);
+                grid.set_vertical(col, row, passages[rng.gen_range(0..passages.len())]);
+            }
+        }
+        for row in 0..3 {
+            for col in 0..3 {
+                grid.set_item(row, col, items[rng.gen_range(0..items.len())]);
+            }
+        }
+        grid
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if self.demo_mode {
+            // Mise à jour aléatoire de la vue toutes les 2 secondes pour simuler le serveur.
+            let time = ui.ctx().input(|i| i.time);
+            if self.radar.is_none() || time - self.last_update > 2.0 {
+                self.last_update = time;
+                self.radar = Some(Self::random_radar());
             }
         }
-        
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Vue Radar du Labyrinthe (Dynamique)");
-            let cell_size = 50.0;
-            ui.horizontal_centered(|ui| {
-                for (i, row) in self.grid.iter().enumerate() {
-                    ui.vertical(|ui| {
-                        for (j, &cell) in row.iter().enumerate() {
-                            let (rect, _response) = ui.allocate_exact_size(egui::Vec2::new(cell_size, cell_size), egui::Sense::hover());
-                            let color = if cell {
-                                egui::Color32::DARK_GRAY
-                            } else if (i, j) == self.player_pos {
-                                egui::Color32::GREEN
-                            } else {
-                                egui::Color32::LIGHT_GRAY
-                            };
-                            ui.painter().rect_filled(rect, 2.0, color);
-                        }
-                    });
+        // Les RadarView arrivent par le réseau sans interaction : on redessine régulièrement.
+        ui.ctx().request_repaint_after(Duration::from_millis(100));
+
+        let title = if self.demo_mode { "Vue Radar du Labyrinthe (Démo)" } else { "Vue Radar du Labyrinthe" };
+        ui.heading(title);
+        let Some(radar) = self.radar else {
+            ui.label("En attente de la première RadarView du serveur…");
+            return;
+        };
+
+        let cell_size = 90.0;
+        let side = cell_size * 3.0;
+        let (rect, _response) = ui.allocate_exact_size(egui::Vec2::splat(side), egui::Sense::hover());
+        let painter = ui.painter_at(rect.expand(4.0));
+        let origin = rect.min;
+
+        for row in 0..3 {
+            for col in 0..3 {
+                let min = origin + egui::vec2(col as f32 * cell_size, row as f32 * cell_size);
+                let cell = egui::Rect::from_min_size(min, egui::Vec2::splat(cell_size)).shrink(2.0);
+                let color = if (row, col) == (1, 1) {
+                    egui::Color32::from_rgb(120, 200, 120)
+                } else {
+                    egui::Color32::LIGHT_GRAY
+                };
+                painter.rect_filled(cell, 2.0, color);
+                if let Some(icon) = item_icon(radar.item(row, col)) {
+                    painter.text(
+                        cell.center(),
+                        egui::Align2::CENTER_CENTER,
+                        icon,
+                        egui::FontId::proportional(cell_size * 0.5),
+                        egui::Color32::BLACK,
+                    );
                 }
-            });
-            ui.add_space(20.0);
-        });
+            }
+        }
+
+        for row in 0..4 {
+            for col in 0..3 {
+                let y = origin.y + row as f32 * cell_size;
+                let from = egui::pos2(origin.x + col as f32 * cell_size, y);
+                let to = egui::pos2(from.x + cell_size, y);
+                draw_passage(&painter, radar.horizontal(row, col), from, to);
+            }
+        }
+        for row in 0..3 {
+            for col in 0..4 {
+                let x = origin.x + col as f32 * cell_size;
+                let from = egui::pos2(x, origin.y + row as f32 * cell_size);
+                let to = egui::pos2(x, from.y + cell_size);
+                draw_passage(&painter, radar.vertical(row, col), from, to);
+            }
+        }
+        ui.add_space(20.0);
+    }
+}
+
+/// Panneau des indices : taille du labyrinthe, boussole et secret de chaque joueur, demandes d'aide.
+pub fn hints_panel(ui: &mut egui::Ui, hints: &HintBook) {
+    ui.heading(egui::RichText::new("Indices").size(22.0).color(egui::Color32::WHITE));
+    ui.separator();
+    match hints.grid_size() {
+        Some((columns, rows)) => ui.label(format!("Labyrinthe : {} x {}", columns, rows)),
+        None => ui.label("Labyrinthe : taille inconnue"),
+    };
+    ui.separator();
+    ui.label(egui::RichText::new("Boussole :").color(egui::Color32::LIGHT_GRAY));
+    for (player, angle) in hints.compasses() {
+        ui.label(format!("{} : sortie à {:.0}°", player, angle));
+    }
+    ui.separator();
+    ui.label(egui::RichText::new("Secrets :").color(egui::Color32::LIGHT_GRAY));
+    for (player, secret) in hints.secrets() {
+        ui.label(format!("{} : {}", player, secret));
+    }
+    let helpers: Vec<&str> = hints.sos_helpers().collect();
+    if !helpers.is_empty() {
+        ui.separator();
+        ui.label(egui::RichText::new(format!("🆘 Aide demandée à : {}", helpers.join(", "))).color(egui::Color32::YELLOW));
+    }
+}
+
+/// Icône associée à un élément de la vue radar.
+fn item_icon(item: RadarItem) -> Option<&'static str> {
+    match item {
+        RadarItem::None => None,
+        RadarItem::Ally => Some("🙂"),
+        RadarItem::Enemy => Some("⚔"),
+        RadarItem::Monster => Some("👾"),
+        RadarItem::Hint => Some("💡"),
+        RadarItem::Target => Some("🏁"),
+    }
+}
+
+fn draw_passage(painter: &egui::Painter, passage: Passage, from: egui::Pos2, to: egui::Pos2) {
+    match passage {
+        Passage::Wall => {
+            painter.line_segment([from, to], egui::Stroke::new(5.0, egui::Color32::from_gray(30)));
+        }
+        Passage::Undefined => {
+            painter.extend(egui::Shape::dashed_line(
+                &[from, to],
+                egui::Stroke::new(1.5, egui::Color32::from_gray(140)),
+                6.0,
+                4.0,
+            ));
+        }
+        Passage::Open => {}
     }
 }
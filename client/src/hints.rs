@@ -0,0 +1,99 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::session::SessionEvent;
+use shared::messages::{secret_sum_modulo, Challenge, Hint};
+
+/// Indices reçus par les joueurs de l'équipe : dernière boussole et dernier secret de chaque joueur,
+/// taille du labyrinthe et demandes d'aide (SOS) en attente.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HintBook {
+    compass: BTreeMap<String, f32>,
+    grid_size: Option<(u32, u32)>,
+    secrets: BTreeMap<String, u64>,
+    sos_helpers: BTreeSet<String>,
+}
+
+impl HintBook {
+    /// Enregistre un indice reçu par `player_name`.
+    pub fn record(&mut self, player_name: &str, hint: &Hint) {
+        match hint {
+            Hint::RelativeCompass { angle } => {
+                self.compass.insert(player_name.to_string(), *angle);
+            }
+            Hint::GridSize { columns, rows } => self.grid_size = Some((*columns, *rows)),
+            Hint::Secret(secret) => {
+                self.secrets.insert(player_name.to_string(), *secret);
+            }
+            Hint::SOSHelper => {
+                self.sos_helpers.insert(player_name.to_string());
+            }
+        }
+    }
+
+    /// Enregistre les indices présents dans les événements de session d'un joueur.
+    pub fn observe(&mut self, player_name: &str, events: &[SessionEvent]) {
+        for event in events {
+            if let SessionEvent::Hint(hint) = event {
+                self.record(player_name, hint);
+            }
+        }
+    }
+
+    /// Taille du labyrinthe `(colonnes, lignes)`, si le serveur l'a annoncée.
+    pub fn grid_size(&self) -> Option<(u32, u32)> {
+        self.grid_size
+    }
+
+    pub fn secrets(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.secrets.iter().map(|(name, secret)| (name.as_str(), *secret))
+    }
+
+    /// Dernier angle de boussole de chaque joueur, en degrés relatifs à son orientation d'alors.
+    pub fn compasses(&self) -> impl Iterator<Item = (&str, f32)> {
+        self.compass.iter().map(|(name, angle)| (name.as_str(), *angle))
+    }
+
+    /// Joueurs invités par le serveur à aider un coéquipier en SOS.
+    pub fn sos_helpers(&self) -> impl Iterator<Item = &str> {
+        self.sos_helpers.iter().map(String::as_str)
+    }
+
+    /// Réponse à un challenge, si elle peut être calculée à partir des secrets connus.
+    /// Un `SOS` n'a pas de réponse : il faut attendre qu'un coéquipier vienne aider.
+    pub fn answer(&self, challenge: &Challenge) -> Option<String> {
+        match challenge {
+            Challenge::SecretSumModulo(0) | Challenge::SOS => None,
+            Challenge::SecretSumModulo(modulo) => Some(secret_sum_modulo(self.secrets.values().copied(), *modulo).to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_latest_hints() {
+        let mut book = HintBook::default();
+        book.record("a", &Hint::RelativeCompass { angle: 90.0 });
+        book.record("a", &Hint::RelativeCompass { angle: 45.0 });
+        book.record("b", &Hint::GridSize { columns: 16, rows: 10 });
+        book.observe("b", &[SessionEvent::Hint(Hint::Secret(4)), SessionEvent::RadarView("x".into())]);
+        book.record("c", &Hint::SOSHelper);
+
+        assert_eq!(book.compasses().collect::<Vec<_>>(), vec![("a", 45.0)]);
+        assert_eq!(book.grid_size(), Some((16, 10)));
+        assert_eq!(book.secrets().collect::<Vec<_>>(), vec![("b", 4)]);
+        assert_eq!(book.sos_helpers().collect::<Vec<_>>(), vec!["c"]);
+    }
+
+    #[test]
+    fn test_answer_is_sum_modulo() {
+        let mut book = HintBook::default();
+        book.record("a", &Hint::Secret(u64::MAX));
+        book.record("b", &Hint::Secret(3));
+        book.record("a", &Hint::Secret(10));
+        assert_eq!(book.answer(&Challenge::SecretSumModulo(7)), Some("6".to_string()));
+        assert_eq!(book.answer(&Challenge::SOS), None);
+    }
+}
@@ -0,0 +1,12 @@
+pub mod bot;
+pub mod challenge;
+pub mod coordinator;
+pub mod error;
+pub mod game;
+pub mod hints;
+pub mod loadtest;
+pub mod maze_solver;
+pub mod network;
+pub mod session;
+pub mod strategy;
+pub mod world_map;
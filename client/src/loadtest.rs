@@ -0,0 +1,364 @@
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::bot::{self, BotConfig, BotError, Pace, RequestHook};
+use crate::error::ClientError;
+use crate::strategy;
+use shared::config::ServerAddress;
+
+/// Rythmes acceptés en dehors de 0, en tours par seconde et par joueur : de un tour toutes
+/// les 1000 secondes à 10 000 tours par seconde.
+pub const RATE_RANGE: RangeInclusive<f64> = 0.001..=10_000.0;
+
+/// Paramètres d'un test de charge : `teams` équipes de `players` joueurs, chacun piloté par `strategy`.
+#[derive(Debug, Clone)]
+pub struct LoadConfig {
+    pub server_address: String,
+    /// Préfixe des noms d'équipe, suivi du numéro de l'équipe.
+    pub team_prefix: String,
+    pub teams: usize,
+    pub players: usize,
+    pub strategy: String,
+    /// Tours par seconde et par joueur, dans [`RATE_RANGE`] ; 0 pour jouer aussi vite que le serveur répond.
+    pub rate: f64,
+    /// Nombre maximal de tours par joueur : déplacements tentés et attentes.
+    pub max_moves: u32,
+    /// Durée maximale du test, inscriptions comprises.
+    pub duration: Option<Duration>,
+    pub seed: u64,
+}
+
+impl Default for LoadConfig {
+    fn default() -> Self {
+        Self {
+            server_address: ServerAddress::default().to_string(),
+            team_prefix: format!("load-{}", std::process::id()),
+            teams: 4,
+            players: 3,
+            strategy: "right-hand".to_string(),
+            rate: 0.0,
+            max_moves: 1_000,
+            duration: None,
+            seed: 0,
+        }
+    }
+}
+
+/// Mesures brutes d'un joueur ou d'une équipe, fusionnées à la fin du test.
+#[derive(Debug, Default)]
+struct Stats {
+    /// Temps de réponse par type de requête.
+    latencies: BTreeMap<&'static str, Vec<Duration>>,
+    action_errors: BTreeMap<String, u64>,
+    disconnects: u64,
+    failures: BTreeMap<String, u64>,
+    escaped: u64,
+}
+
+impl RequestHook for Stats {
+    fn on_request(&mut self, kind: &'static str, elapsed: Duration) {
+        self.latencies.entry(kind).or_default().push(elapsed);
+    }
+
+    fn on_failure(&mut self, error: &ClientError) {
+        self.record_error(error);
+    }
+}
+
+impl Stats {
+    /// Classe une erreur : coupure de la connexion par le serveur, ou échec nommé d'après sa cause.
+    fn record_error(&mut self, error: &ClientError) {
+        let kind = match error {
+            ClientError::Io(_) | ClientError::Framing(_) => {
+                self.disconnects += 1;
+                return;
+            }
+            ClientError::Connect { .. } => "Connect".to_string(),
+            ClientError::Json(_) => "Json".to_string(),
+            ClientError::RegisterTeam(reason) => format!("RegisterTeam: {}", reason),
+            ClientError::SubscribePlayer(reason) => format!("SubscribePlayer: {}", reason),
+            ClientError::Unexpected(_) => "Unexpected".to_string(),
+            ClientError::InvalidRadar(_) => "InvalidRadar".to_string(),
+        };
+        *self.failures.entry(kind).or_insert(0) += 1;
+    }
+
+    fn merge(&mut self, other: Stats) {
+        for (kind, samples) in other.latencies {
+            self.latencies.entry(kind).or_default().extend(samples);
+        }
+        for (error, count) in other.action_errors {
+            *self.action_errors.entry(error).or_insert(0) += count;
+        }
+        for (failure, count) in other.failures {
+            *self.failures.entry(failure).or_insert(0) += count;
+        }
+        self.disconnects += other.disconnects;
+        self.escaped += other.escaped;
+    }
+}
+
+/// Temps de réponse d'un type de requête.
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencySummary {
+    pub count: usize,
+    /// Requêtes par seconde sur toute la durée du test.
+    pub throughput: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    fn new(samples: &mut [Duration], elapsed: Duration) -> Self {
+        samples.sort_unstable();
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let total: Duration = samples.iter().sum();
+        Self {
+            count: samples.len(),
+            throughput: samples.len() as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+            mean_ms: ms(total) / samples.len().max(1) as f64,
+            p50_ms: ms(percentile(samples, 50.0)),
+            p90_ms: ms(percentile(samples, 90.0)),
+            p99_ms: ms(percentile(samples, 99.0)),
+            max_ms: ms(samples.last().copied().unwrap_or_default()),
+        }
+    }
+}
+
+/// Percentile au rang le plus proche d'un échantillon trié ; zéro pour un échantillon vide.
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Bilan d'un test de charge, affiché en tableau ou sérialisé en JSON pour suivre son évolution.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadReport {
+    pub server_address: String,
+    pub teams: usize,
+    pub players_per_team: usize,
+    pub strategy: String,
+    pub rate: f64,
+    pub elapsed_secs: f64,
+    /// Requêtes abouties, tous types confondus.
+    pub requests: usize,
+    pub throughput: f64,
+    /// Joueurs inscrits, et parmi eux ceux qui ont atteint la sortie.
+    pub players: usize,
+    pub escaped: u64,
+    /// Clés : `RegisterTeam`, `SubscribePlayer`, `MoveTo` et `SolveChallenge`.
+    pub latencies: BTreeMap<String, LatencySummary>,
+    /// Nombre d'erreurs par variante d'`ActionError`.
+    pub action_errors: BTreeMap<String, u64>,
+    /// Connexions coupées ou perdues en cours de partie.
+    pub disconnects: u64,
+    /// Autres échecs : connexion impossible, inscription refusée, message inattendu.
+    pub failures: BTreeMap<String, u64>,
+}
+
+impl LoadReport {
+    fn new(config: &LoadConfig, mut stats: Stats, elapsed: Duration) -> Self {
+        let latencies: BTreeMap<String, LatencySummary> = stats.latencies.iter_mut()
+            .map(|(kind, samples)| (kind.to_string(), LatencySummary::new(samples, elapsed)))
+            .collect();
+        let requests = latencies.values().map(|l| l.count).sum();
+        Self {
+            server_address: config.server_address.clone(),
+            teams: config.teams,
+            players_per_team: config.players,
+            strategy: config.strategy.clone(),
+            rate: config.rate,
+            elapsed_secs: elapsed.as_secs_f64(),
+            requests,
+            throughput: requests as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+            players: latencies.get("SubscribePlayer").map_or(0, |l| l.count),
+            escaped: stats.escaped,
+            latencies,
+            action_errors: stats.action_errors,
+            disconnects: stats.disconnects,
+            failures: stats.failures,
+        }
+    }
+
+    /// Tableau lisible du bilan.
+    pub fn table(&self) -> String {
+        let mut lines = vec![format!(
+            "{:<16} {:>9} {:>9} {:>9} {:>9} {:>9} {:>9} {:>9}",
+            "requête", "nombre", "req/s", "moy (ms)", "p50", "p90", "p99", "max"
+        )];
+        for (kind, l) in &self.latencies {
+            lines.push(format!(
+                "{:<16} {:>9} {:>9.1} {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>9.2}",
+                kind, l.count, l.throughput, l.mean_ms, l.p50_ms, l.p90_ms, l.p99_ms, l.max_ms
+            ));
+        }
+        for (title, counts) in [("Erreurs d'action", &self.action_errors), ("Échecs", &self.failures)] {
+            if !counts.is_empty() {
+                lines.push(format!("{} :", title));
+                lines.extend(counts.iter().map(|(kind, count)| format!("    {:<30} {:>6}", kind, count)));
+            }
+        }
+        lines.push(format!("Déconnexions : {}", self.disconnects));
+        lines.push(format!(
+            "Total : {} requêtes en {:.1} s ({:.1} req/s), {}/{} joueurs sortis",
+            self.requests, self.elapsed_secs, self.throughput, self.escaped, self.players
+        ));
+        lines.join("\n")
+    }
+}
+
+/// Lance le test : chaque équipe joue dans son propre thread comme le bot (voir [`bot::run`]),
+/// jusqu'à la sortie, `max_moves` tours ou la fin de `duration`.
+/// Les refus et coupures du serveur sont comptés dans le bilan sans interrompre le test.
+pub fn run(config: &LoadConfig) -> Result<LoadReport, BotError> {
+    if strategy::by_name(&config.strategy, 0).is_none() {
+        return Err(BotError::UnknownStrategy(config.strategy.clone()));
+    }
+    let start = Instant::now();
+    let pace = Pace {
+        interval: (config.rate > 0.0).then(|| Duration::from_secs_f64(1.0 / config.rate)),
+        deadline: config.duration.map(|duration| start + duration),
+    };
+    let handles: Vec<_> = (0..config.teams)
+        .map(|team| {
+            let config = BotConfig {
+                server_address: config.server_address.clone(),
+                team_name: format!("{}-{}", config.team_prefix, team),
+                players: config.players,
+                strategy: config.strategy.clone(),
+                max_moves: config.max_moves,
+                seed: config.seed.wrapping_add((team * config.players) as u64),
+                coordinated: false,
+            };
+            thread::spawn(move || run_team(&config, pace))
+        })
+        .collect();
+    let mut stats = Stats::default();
+    for handle in handles {
+        stats.merge(handle.join().expect("thread d'équipe"));
+    }
+    Ok(LoadReport::new(config, stats, start.elapsed()))
+}
+
+/// Fait jouer une équipe et rassemble les mesures de ses joueurs. Une inscription refusée
+/// est comptée parmi les échecs, et l'équipe ne joue pas.
+fn run_team(config: &BotConfig, pace: Pace) -> Stats {
+    let mut stats = Stats::default();
+    match bot::run_with(config, pace, &mut stats) {
+        Ok(players) => {
+            for (report, player_stats) in players {
+                stats.merge(player_stats);
+                for (error, count) in report.errors {
+                    *stats.action_errors.entry(error).or_insert(0) += u64::from(count);
+                }
+                stats.escaped += u64::from(report.reached_target);
+            }
+        }
+        Err(BotError::Registration(_, e) | BotError::Session(_, e)) => stats.record_error(&e),
+        Err(BotError::UnknownStrategy(_)) => unreachable!("stratégie vérifiée"),
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use server::challenge::ChallengeRules;
+    use server::game::{GameConfig, GameState};
+    use server::maze::generators::Algorithm;
+    use server::network::{spawn_with, ServerConfig};
+
+    #[test]
+    fn test_percentile() {
+        let samples: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&samples, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&samples, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&samples, 100.0), Duration::from_millis(100));
+        assert_eq!(percentile(&samples[..1], 90.0), Duration::from_millis(1));
+        assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_teams_play_and_report() {
+        let config = GameConfig {
+            players_per_team: 2,
+            challenges: ChallengeRules { secret_sum_every: Some(4), sos_every: None },
+            ..GameConfig::default()
+        };
+        let maze = Algorithm::RecursiveBacktracker.generate(6, 5, 11);
+        let game = GameState::new(maze, config);
+        let server = spawn_with("127.0.0.1:0", game, ServerConfig::default()).unwrap();
+
+        let report = run(&LoadConfig {
+            server_address: server.address().to_string(),
+            teams: 3,
+            players: 2,
+            max_moves: 500,
+            ..LoadConfig::default()
+        })
+        .unwrap();
+        assert_eq!(report.latencies["RegisterTeam"].count, 3);
+        assert_eq!(report.players, 6);
+        // Les joueurs sortis restent sur la sortie et la ferment aux autres équipes : seule la première passe sûrement.
+        assert!(report.escaped >= 1, "{}", report.table());
+        assert!(report.latencies["MoveTo"].count >= 6);
+        assert!(report.latencies.contains_key("SolveChallenge"));
+        assert_eq!(report.disconnects, 0);
+        assert!(!report.action_errors.contains_key("InvalidChallengeSolution"));
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["players"], 6);
+        assert!(json["latencies"]["MoveTo"]["p99_ms"].as_f64().unwrap() >= json["latencies"]["MoveTo"]["p50_ms"].as_f64().unwrap());
+    }
+
+    #[test]
+    fn test_counts_disconnects() {
+        // Le serveur coupe les joueurs trop lents.
+        let server_config = ServerConfig { read_timeout: Duration::from_millis(50), ..ServerConfig::default() };
+        let game = GameState::new(Algorithm::RecursiveBacktracker.generate(6, 5, 11), GameConfig::default());
+        let server = spawn_with("127.0.0.1:0", game, server_config).unwrap();
+        let players_per_team = GameConfig::default().players_per_team as usize;
+
+        let report = run(&LoadConfig {
+            server_address: server.address().to_string(),
+            teams: 2,
+            players: players_per_team,
+            rate: 4.0,
+            ..LoadConfig::default()
+        })
+        .unwrap();
+        assert_eq!(report.players, 2 * players_per_team);
+        // Un joueur peut sortir dès son premier déplacement, avant d'être coupé.
+        assert_eq!(report.disconnects + report.escaped, 2 * players_per_team as u64, "{}", report.table());
+        assert!(report.failures.is_empty(), "{:?}", report.failures);
+    }
+
+    #[test]
+    fn test_counts_refusals() {
+        // Le serveur refuse le joueur en trop de chaque équipe.
+        let game = GameState::new(Algorithm::RecursiveBacktracker.generate(6, 5, 11), GameConfig::default());
+        let server = spawn_with("127.0.0.1:0", game, ServerConfig::default()).unwrap();
+        let players_per_team = GameConfig::default().players_per_team as usize;
+
+        let report = run(&LoadConfig {
+            server_address: server.address().to_string(),
+            teams: 2,
+            players: players_per_team + 1,
+            ..LoadConfig::default()
+        })
+        .unwrap();
+        assert_eq!(report.players, 2 * players_per_team);
+        assert_eq!(report.failures.values().sum::<u64>(), 2, "{:?}", report.failures);
+        assert!(!report.latencies.contains_key("MoveTo"));
+    }
+}
@@ -1,117 +1,234 @@
 mod team_gui;
-mod game;
-mod network;
 mod game_gui;
 mod app;
 
 use eframe::run_native;
-use std::sync::mpsc::channel;
+use std::env;
+use std::process::ExitCode;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
 use crate::app::MainApp;
 use crate::team_gui::RegistrationData;
-use crate::game::GameClient;
+use client::challenge::perform_solving;
+use client::game::GameClient;
+use client::hints::HintBook;
+use client::session::{last_radar, PlayerSession, SessionCommand, SessionEvent};
+use client::strategy::{self, Strategy};
+use shared::config::{self, ServerAddress};
+use shared::messages::{Action, Hint};
+use shared::radar::RadarGrid;
+
+fn main() -> ExitCode {
+    let server_address = match ServerAddress::from_env_and_args(env::args().skip(1)) {
+        Ok((address, rest)) if rest.is_empty() => address.to_string(),
+        Ok((_, rest)) => {
+            eprintln!("option inconnue: {}\nUsage: client {}", rest[0], config::USAGE);
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            eprintln!("{}\nUsage: client {}", e, config::USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
 
-fn main() {
     // Création des canaux
     let (tx_gui_net, rx_gui_net) = channel::<RegistrationData>();
     let (tx_net_gui, rx_net_gui) = channel::<app::NetworkMessage>();
+    let (tx_session, rx_session) = channel::<SessionCommand>();
 
     // Lancement du thread réseau
+    let network_address = server_address.clone();
     std::thread::spawn(move || {
-        if let Ok(reg_data) = rx_gui_net.recv() {
+        let server_address = network_address.as_str();
+        // Chaque réponse du serveur est rendue à l'interface, qui propose de réessayer ou de corriger.
+        let mut lobby = Lobby::default();
+        while let Ok(reg_data) = rx_gui_net.recv() {
             match reg_data {
                 RegistrationData::Create { team_name, team_members } => {
-                    println!("[Network] Création d'équipe: {} {:?}", team_name, team_members);
-                    let server_address = "127.0.0.1:8778";
-                    let mut client = GameClient::new(server_address);
-                    client.register_team(&team_name);
-                    if let Some(token) = client.registration_token.clone() {
-                        tx_net_gui.send(app::NetworkMessage::RegistrationComplete {
-                            token: token.clone(),
-                            team_name: team_name.clone(),
-                            team_members: team_members.clone(),
-                        }).ok();
-                        for m in &team_members {
-                            println!("[Network] Inscription du joueur: {}", m);
-                            client.subscribe_player(m);
+                    let registered = GameClient::new(server_address)
+                        .and_then(|mut client| client.register_team(&team_name));
+                    match registered {
+                        Ok(ok) => {
+                            tx_net_gui.send(app::NetworkMessage::TeamRegistered {
+                                token: ok.registration_token.clone(),
+                                expected_players: ok.expected_players,
+                            }).ok();
+                            for player in &team_members {
+                                lobby.subscribe(server_address, &ok.registration_token, player, &tx_net_gui);
+                            }
+                        }
+                        Err(e) => {
+                            tx_net_gui.send(app::NetworkMessage::RegistrationFailed(e.to_string())).ok();
                         }
-                        listen_server_loop(server_address.to_string(), tx_net_gui);
-                    } else {
-                        tx_net_gui.send(app::NetworkMessage::RegistrationFailed("Registration error".into())).ok();
                     }
-                },
-                RegistrationData::Join { token, player_name } => {
-                    println!("[Network] Rejoindre l'équipe: token={} player={}", token, player_name);
-                    let server_address = "127.0.0.1:8778";
-                    GameClient::join_game(server_address, &token, &player_name);
-                    tx_net_gui.send(app::NetworkMessage::RegistrationComplete {
-                        token: token.clone(),
-                        team_name: String::new(),
-                        team_members: vec![],
-                    }).ok();
-                    listen_server_loop(server_address.to_string(), tx_net_gui);
+                }
+                RegistrationData::Subscribe { token, player_name } => {
+                    lobby.subscribe(server_address, &token, &player_name, &tx_net_gui);
+                }
+                RegistrationData::Reset => lobby.reset(),
+                RegistrationData::Start => {
+                    run_sessions(lobby.sessions, lobby.hints, lobby.radar, rx_session, tx_net_gui);
+                    return;
                 }
             }
         }
-        loop {
-            std::thread::sleep(std::time::Duration::from_secs(9999));
-        }
+        lobby.reset();
     });
 
-    let app = MainApp::new(rx_net_gui, tx_gui_net);
+    let title = format!("Sauve Qui Peut - {}", server_address);
+    let app = MainApp::new(server_address, rx_net_gui, tx_gui_net, tx_session);
     let native_options = eframe::NativeOptions::default();
-    let _ = run_native("Sauve Qui Peut - Main App", native_options, Box::new(|_cc| Box::new(app)));
+    if let Err(e) = run_native(&title, native_options, Box::new(|_cc| Box::new(app))) {
+        eprintln!("Impossible de lancer l'interface: {}", e);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+/// Joueurs inscrits pendant la phase d'inscription, en attendant le lancement de la partie.
+/// Une session (et donc une connexion) par joueur, pour toute la durée de la partie.
+/// Les indices de tous les joueurs de l'équipe servent à répondre aux challenges.
+#[derive(Default)]
+struct Lobby {
+    sessions: Vec<PlayerSession>,
+    hints: HintBook,
+    radar: Option<RadarGrid>,
 }
 
-fn listen_server_loop(server_address: String, tx_net_gui: std::sync::mpsc::Sender<app::NetworkMessage>) {
-    use std::net::TcpStream;
-    use crate::network::receive_message;
-    let stream = TcpStream::connect(&server_address);
-    if stream.is_err() {
-        eprintln!("Impossible de se connecter à {} dans listen_server_loop", server_address);
-        return;
+impl Lobby {
+    /// Inscrit un joueur et rend compte de la réponse du serveur à l'interface.
+    fn subscribe(&mut self, server_address: &str, token: &str, player: &str, tx_net_gui: &Sender<app::NetworkMessage>) {
+        match PlayerSession::subscribe(server_address, token, player) {
+            Ok((session, radar, events)) => {
+                self.hints.observe(player, &events);
+                // Seul le joueur actif affiche sa vue radar ; les indices de tous sont relayés.
+                let events = if self.sessions.is_empty() {
+                    self.radar = Some(radar);
+                    events
+                } else {
+                    events.into_iter().filter(|e| matches!(e, SessionEvent::Hint(_))).collect()
+                };
+                tx_net_gui.send(app::NetworkMessage::PlayerSubscribed(player.to_string())).ok();
+                forward_events(player, events, tx_net_gui);
+                self.sessions.push(session);
+            }
+            Err(e) => {
+                let reason = e.to_string();
+                tx_net_gui.send(app::NetworkMessage::PlayerRejected { player: player.to_string(), reason }).ok();
+            }
+        }
     }
-    let mut stream = stream.unwrap();
-    stream.set_nonblocking(false).ok();
 
+    /// Ferme les sessions ouvertes pour recommencer l'inscription.
+    fn reset(&mut self) {
+        for session in self.sessions.drain(..) {
+            session.shutdown().ok();
+        }
+        self.hints = HintBook::default();
+        self.radar = None;
+    }
+}
+
+/// Délai entre deux déplacements du pilote automatique.
+const AUTOPILOT_DELAY: Duration = Duration::from_millis(300);
+
+/// Exécute les commandes de l'interface sur la session du joueur actif (le premier inscrit)
+/// et relaie les réponses du serveur, jusqu'à la demande d'arrêt ou la fermeture de l'interface.
+/// Les challenges `SecretSumModulo` sont résolus automatiquement. Quand le pilote automatique
+/// est actif, la stratégie choisie joue un déplacement à chaque `AUTOPILOT_DELAY` sans commande.
+fn run_sessions(
+    mut sessions: Vec<PlayerSession>,
+    mut hints: HintBook,
+    mut radar: Option<RadarGrid>,
+    rx_session: Receiver<SessionCommand>,
+    tx_net_gui: Sender<app::NetworkMessage>,
+) {
+    let mut autopilot: Option<Box<dyn Strategy>> = None;
     loop {
-        if let Some(json_str) = receive_message(&mut stream) {
-            if json_str.contains("\"RadarView\"") {
-                if let Ok(rv) = serde_json::from_str::<RadarViewWrapper>(&json_str) {
-                    tx_net_gui.send(app::NetworkMessage::RadarViewReceived(rv.radar_view.0)).ok();
+        let command = if autopilot.is_some() {
+            match rx_session.recv_timeout(AUTOPILOT_DELAY) {
+                Ok(command) => Some(command),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            match rx_session.recv() {
+                Ok(command) => Some(command),
+                Err(_) => break,
+            }
+        };
+        let action = match command {
+            Some(SessionCommand::Action(action)) => action,
+            Some(SessionCommand::Autopilot(name)) => {
+                autopilot = name.and_then(|name| {
+                    let strategy = strategy::by_name(&name, 0);
+                    if strategy.is_none() {
+                        tx_net_gui.send(app::NetworkMessage::Error(format!("Stratégie inconnue: {}", name))).ok();
+                    }
+                    strategy
+                });
+                if let (Some(strategy), Some((columns, rows))) = (autopilot.as_mut(), hints.grid_size()) {
+                    strategy.on_hint(&Hint::GridSize { columns, rows });
                 }
-            } else if json_str.contains("\"Hint\"") {
-                if let Ok(hw) = serde_json::from_str::<HintWrapper>(&json_str) {
-                    tx_net_gui.send(app::NetworkMessage::HintReceived(serde_json::to_value(hw.hint).unwrap())).ok();
+                continue;
+            }
+            Some(SessionCommand::Shutdown) => break,
+            None => {
+                let (Some(strategy), Some(radar)) = (autopilot.as_mut(), radar.as_ref()) else {
+                    continue;
+                };
+                let planned = strategy.next_move(radar);
+                match strategy::target_direction(radar).or(planned) {
+                    Some(direction) => Action::MoveTo(direction),
+                    None => {
+                        strategy.on_wait();
+                        continue;
+                    }
+                }
+            }
+        };
+        let Some(session) = sessions.first_mut() else {
+            let error = format!("Aucun joueur inscrit pour l'action {:?}", action);
+            tx_net_gui.send(app::NetworkMessage::Error(error)).ok();
+            autopilot = None;
+            continue;
+        };
+        match perform_solving(session, &mut hints, action.clone()) {
+            Ok(events) => {
+                if let Some(strategy) = autopilot.as_mut() {
+                    strategy::feed(strategy.as_mut(), &action, &events);
                 }
-            } else if json_str.contains("\"Challenge\"") {
-                if let Ok(cw) = serde_json::from_str::<ChallengeWrapper>(&json_str) {
-                    tx_net_gui.send(app::NetworkMessage::ChallengeReceived(serde_json::to_value(cw.challenge).unwrap())).ok();
+                if let Some(next) = last_radar(&events) {
+                    if strategy::reached_target(&next) {
+                        autopilot = None;
+                    }
+                    radar = Some(next);
                 }
-            } else if json_str.contains("\"ActionError\"") {
-                tx_net_gui.send(app::NetworkMessage::ActionErrorReceived(json_str)).ok();
-            } else {
-                eprintln!("Message inconnu: {}", json_str);
+                forward_events(&session.player_name, events, &tx_net_gui);
+            }
+            Err(e) => {
+                let error = format!("Connexion de {} perdue: {}", session.player_name, e);
+                tx_net_gui.send(app::NetworkMessage::Error(error)).ok();
+                sessions.remove(0);
             }
-        } else {
-            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+    }
+    for session in sessions {
+        if let Err(e) = session.shutdown() {
+            eprintln!("[Network] Fermeture de session impossible: {}", e);
         }
     }
 }
 
-#[derive(serde::Deserialize)]
-struct RadarViewWrapper {
-    #[serde(rename = "RadarView")]
-    radar_view: shared::messages::RadarView,
-}
-
-#[derive(serde::Deserialize)]
-struct HintWrapper {
-    #[serde(rename = "Hint")]
-    hint: shared::messages::Hint,
-}
-
-#[derive(serde::Deserialize)]
-struct ChallengeWrapper {
-    #[serde(rename = "Challenge")]
-    challenge: shared::messages::Challenge,
+/// Relaie à l'interface les événements de la session d'un joueur.
+fn forward_events(player: &str, events: Vec<SessionEvent>, tx_net_gui: &Sender<app::NetworkMessage>) {
+    for event in events {
+        let msg = match event {
+            SessionEvent::RadarView(rv) => app::NetworkMessage::RadarViewReceived(rv),
+            SessionEvent::Hint(hint) => app::NetworkMessage::HintReceived { player: player.to_string(), hint },
+            SessionEvent::Challenge(challenge) => app::NetworkMessage::ChallengeReceived(challenge),
+            SessionEvent::ActionError(err) => app::NetworkMessage::ActionErrorReceived(err),
+        };
+        tx_net_gui.send(msg).ok();
+    }
 }
@@ -1,69 +1,23 @@
-use std::collections::VecDeque;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, Write};
+use std::path::Path;
 
-/// Position dans le laby
-pub type Position = (usize, usize);
-
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-impl Direction {
-    /// Retourne la direction après avoir tourner à gauche (pour pas être désorienté)
-    pub fn turn_left(self) -> Self {
-        match self {
-            Direction::North => Direction::West,
-            Direction::West  => Direction::South,
-            Direction::South => Direction::East,
-            Direction::East  => Direction::North,
-        }
-    }
-
-    /// pareil mais après un tour à droite.
-    pub fn turn_right(self) -> Self {
-        match self {
-            Direction::North => Direction::East,
-            Direction::East  => Direction::South,
-            Direction::South => Direction::West,
-            Direction::West  => Direction::North,
-        }
-    }
-
-    /// Donne le décalage ligne/colonne correspondant à la direction.
-    pub fn as_offset(self) -> (i32, i32) {
-        match self {
-            Direction::North => (-1, 0),
-            Direction::South => (1, 0),
-            Direction::East  => (0, 1),
-            Direction::West  => (0, -1),
-        }
-    }
-}
-
-
-fn can_move(grid: &Vec<Vec<bool>>, pos: Position) -> bool {
-    let (i, j) = pos;
-    if i < grid.len() && j < grid[0].len() {
-        grid[i][j]
-    } else {
-        false
-    }
-}
+use crate::world_map::{self, MapPos, WorldMap};
+use shared::maze::{Direction, Maze, Position};
+use shared::messages::RelativeDirection;
+use shared::radar::Passage;
 
 /// On sors du labyrinthe avec l'algo de Pledge.
-/// * `grid` : grille du labyrinthe (true = passage, false = mur)
+/// * `maze` : passages du labyrinthe (seuls les passages ouverts sont franchis)
 /// * `start` : position de départ
 /// * `exit` : position de sortie
 /// * `goal` : direction souhaitée (ex. la direction approximative vers la sortie)
+///
 /// Retourne un vecteur de positions formant le chemin s'il est trouvé.
 pub fn solve_maze_pledge(
-    grid: &Vec<Vec<bool>>,
+    maze: &Maze,
     start: Position,
     exit: Position,
     goal: Direction,
@@ -76,139 +30,1055 @@ pub fn solve_maze_pledge(
     let mut path = vec![start];
 
     // Limite le nombre d'itérations pour éviter les boucles infini
-    let max_steps = grid.len() * grid[0].len() * 20;
-    
+    let max_steps = maze.rows() * maze.columns() * 20;
+
     for _ in 0..max_steps {
         if current == exit {
             return Some(path);
         }
-        
+
         if !in_pledge {
             // Essayer d'avancer dans la direction "goal" (la sortie)
-            let (di, dj) = goal.as_offset();
-            let new_i = current.0 as i32 + di;
-            let new_j = current.1 as i32 + dj;
-            if new_i >= 0 && new_j >= 0 {
-                let new_pos = (new_i as usize, new_j as usize);
-                if can_move(grid, new_pos) {
-                    current = new_pos;
-                    path.push(current);
-                    continue;
-                }
+            if let Some(next) = maze.step(current, goal) {
+                current = next;
+                path.push(current);
+                continue;
             }
             // S'il y a un obstacle, activer le mode Pledge.
             in_pledge = true;
-            // On initie la poursuite du mur par un petit virage à droite.
-            current_direction = goal.turn_right();
-            pledge_counter = 0;
+            // On tourne à gauche pour garder le mur à main droite.
+            current_direction = goal.turn_left();
+            pledge_counter = -1;
         }
-        
+
         // En mode Pledge, on suit le mur avec la règle de la main droite.
         // 1. Vérifier si l'on peut tourner à droite.
         let right_dir = current_direction.turn_right();
-        let (di, dj) = right_dir.as_offset();
-        let pos_right = (
-            current.0 as i32 + di,
-            current.1 as i32 + dj,
-        );
-        let can_right = pos_right.0 >= 0
-            && pos_right.1 >= 0
-            && can_move(grid, (pos_right.0 as usize, pos_right.1 as usize));
-
-        if can_right {
+        if let Some(next) = maze.step(current, right_dir) {
             current_direction = right_dir;
             pledge_counter += 1;
-            let (di, dj) = current_direction.as_offset();
-            current = ((current.0 as i32 + di) as usize, (current.1 as i32 + dj) as usize);
+            current = next;
+            path.push(current);
+        } else if let Some(next) = maze.step(current, current_direction) {
+            // Sinon, on avance dans la direction actuelle.
+            current = next;
             path.push(current);
         } else {
-            // Sinon, si on peut avancer dans la direction actuelle.
-            let (di, dj) = current_direction.as_offset();
-            let pos_forward = (
-                current.0 as i32 + di,
-                current.1 as i32 + dj,
-            );
-            let can_forward = pos_forward.0 >= 0
-                && pos_forward.1 >= 0
-                && can_move(grid, (pos_forward.0 as usize, pos_forward.1 as usize));
-            if can_forward {
-                current = ((current.0 as i32 + di) as usize, (current.1 as i32 + dj) as usize);
-                path.push(current);
-            } else {
-                // Si rien ne va, tourner à gauche.
-                current_direction = current_direction.turn_left();
-                pledge_counter -= 1;
-                // On ne bouge pas pour ce tour, juste la direction change.
-            }
+            // Si rien ne va, tourner à gauche.
+            current_direction = current_direction.turn_left();
+            pledge_counter -= 1;
+            // On ne bouge pas pour ce tour, juste la direction change.
         }
-        
+
         // Si le compteur de Pledge est revenu à zéro et que le chemin dans la direction goal est libre,
         // on quitte le mode Pledge.
-        if in_pledge && pledge_counter == 0 {
-            let (di, dj) = goal.as_offset();
-            let pos_goal = (
-                current.0 as i32 + di,
-                current.1 as i32 + dj,
-            );
-            if pos_goal.0 >= 0 && pos_goal.1 >= 0 && can_move(grid, (pos_goal.0 as usize, pos_goal.1 as usize)) {
-                in_pledge = false;
-                current_direction = goal;
-            }
+        if in_pledge && pledge_counter == 0 && maze.step(current, goal).is_some() {
+            in_pledge = false;
+            current_direction = goal;
         }
     }
-    
+
     None // Aucune solution trouvée dans la limite du nombre d'étapes.
 }
 
-/// Génère un fichier  contenant le chemin vers la sortie
-/// pour un labyrinthe d'exemple.
-pub fn generate_solution_file_pledge() {
-    // Exemple de labyrinthe 5x5 (les bords sont des murs).
-    let grid = vec![
-        vec![false, false, false, false, false],
-        vec![false, true,  true,  true,  false],
-        vec![false, true,  false, true,  false],
-        vec![false, true,  true,  true,  false],
-        vec![false, false, false, false, false],
-    ];
-    let start = (1, 1);
-    let exit = (3, 3);
-    let goal = Direction::East; 
-    
-    match solve_maze_pledge(&grid, start, exit, goal) {
-        Some(path) => {
-            let mut file = File::create("maze_solution_pledge.txt")
-                .expect("Impossible de créer maze_solution_pledge.txt");
-            writeln!(file, "Chemin solution (algorithme Pledge):")
-                .expect("Erreur lors de l'écriture");
-            for pos in path {
-                writeln!(file, "{:?}", pos).expect("Erreur lors de l'écriture");
+/// Écrit dans `path` le chemin trouvé par `solver`. Renvoie `false` si aucun chemin n'a été trouvé.
+pub fn write_solution_file(
+    path: &Path,
+    solver: &dyn MazeSolver,
+    maze: &Maze,
+    start: Position,
+    exit: Position,
+) -> io::Result<bool> {
+    let Some(solution) = solver.solve(maze, start, exit) else {
+        return Ok(false);
+    };
+    let mut file = File::create(path)?;
+    writeln!(file, "Chemin solution (algorithme {}, {} étapes):", solver.name(), solution.steps)?;
+    for pos in solution.path {
+        writeln!(file, "{:?}", pos)?;
+    }
+    Ok(true)
+}
+
+/// Distance, en cases, en deçà de laquelle une frontière est réservée par le coéquipier qui la vise.
+const CLAIM_RADIUS: i64 = 2;
+
+/// Poids, en nombre de cases, d'un écart de 90° entre une frontière et le cap de la sortie.
+const COMPASS_WEIGHT: f32 = 2.0;
+
+/// Cap d'une cellule vue depuis `from`, en degrés dans le sens horaire depuis le nord de la carte.
+fn bearing(from: MapPos, to: MapPos) -> f32 {
+    let dr = (to.0 - from.0) as f32;
+    let dc = (to.1 - from.1) as f32;
+    dc.atan2(-dr).to_degrees().rem_euclid(360.0)
+}
+
+fn heading_degrees(facing: world_map::Direction) -> f32 {
+    match facing {
+        world_map::Direction::North => 0.0,
+        world_map::Direction::East => 90.0,
+        world_map::Direction::South => 180.0,
+        world_map::Direction::West => 270.0,
+    }
+}
+
+/// Écart absolu entre deux caps, dans [0, 180].
+fn angle_between(a: f32, b: f32) -> f32 {
+    let diff = (a - b).rem_euclid(360.0);
+    diff.min(360.0 - diff)
+}
+
+/// Planificateur d'exploration en ligne sur une carte partiellement connue.
+/// Tant que la sortie n'est pas accessible, il vise la frontière (cellule connue qui borde
+/// l'inconnu) la plus proche, en favorisant celles qui vont dans le sens de la boussole.
+/// Le chemin, calculé en largeur sur les passages ouverts, contourne les adversaires et
+/// monstres vus récemment ; il est recalculé dès qu'il devient invalide : mur découvert,
+/// menace sur le chemin, frontière explorée ou sortie aperçue.
+#[derive(Debug, Default, Clone)]
+pub struct FrontierPlanner {
+    plan: VecDeque<MapPos>,
+    goal: Option<MapPos>,
+    /// Cap de la sortie dans le repère de la carte, d'après la dernière boussole.
+    compass: Option<f32>,
+    /// Frontières visées par des coéquipiers, à laisser de côté s'il en reste d'autres.
+    claimed: Vec<MapPos>,
+    /// Explore sans viser la sortie, pour un joueur déjà sorti qui cherche un coéquipier.
+    ignore_target: bool,
+}
+
+impl FrontierPlanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enregistre un `Hint::RelativeCompass` reçu alors que le joueur regardait vers `facing`.
+    pub fn observe_compass(&mut self, angle: f32, facing: world_map::Direction) {
+        self.compass = Some((heading_degrees(facing) + angle).rem_euclid(360.0));
+    }
+
+    /// Réserve les frontières visées par les coéquipiers : celles qui en sont à moins de
+    /// `CLAIM_RADIUS` cases ne sont choisies que faute d'autre frontière accessible.
+    pub fn set_claimed(&mut self, claimed: Vec<MapPos>) {
+        self.claimed = claimed;
+    }
+
+    /// N'explore plus que les frontières, même quand la sortie est connue et accessible.
+    pub fn set_ignore_target(&mut self, ignore: bool) {
+        if self.ignore_target != ignore {
+            self.ignore_target = ignore;
+            self.plan.clear();
+        }
+    }
+
+    fn target(&self, map: &WorldMap) -> Option<MapPos> {
+        map.target().filter(|_| !self.ignore_target)
+    }
+
+    fn is_claimed(&self, (r, c): MapPos) -> bool {
+        self.claimed.iter().any(|&(cr, cc)| (r - cr).abs() + (c - cc).abs() <= CLAIM_RADIUS)
+    }
+
+    /// Cellule visée par le plan en cours.
+    pub fn goal(&self) -> Option<MapPos> {
+        self.goal
+    }
+
+    /// Prochain déplacement, relatif à l'orientation actuelle du joueur. Quand plus rien n'est
+    /// accessible sans traverser une menace, le joueur s'écarte d'une case si elle le touche,
+    /// pour ne pas l'enfermer dans une impasse, puis attend qu'elle parte (`None`).
+    pub fn next_move(&mut self, map: &WorldMap) -> Option<RelativeDirection> {
+        let position = map.position();
+        while self.plan.front() == Some(&position) {
+            self.plan.pop_front();
+        }
+        if !self.plan_is_valid(map) {
+            self.replan(map);
+        }
+        let Some(&next) = self.plan.front() else {
+            return retreat(map);
+        };
+        let dir = world_map::Direction::ALL.into_iter().find(|dir| dir.step(position) == next)?;
+        Some(map.facing().relative_to(dir))
+    }
+
+    fn plan_is_valid(&self, map: &WorldMap) -> bool {
+        let Some(goal) = self.goal else {
+            return false;
+        };
+        let target = self.target(map);
+        if self.plan.is_empty() || target.is_some_and(|target| target != goal && reachable(map, target)) {
+            return false;
+        }
+        if Some(goal) != target && (!is_frontier(map, goal) || self.is_claimed(goal)) {
+            return false;
+        }
+        let mut from = map.position();
+        for &to in &self.plan {
+            match world_map::Direction::ALL.into_iter().find(|dir| dir.step(from) == to) {
+                Some(dir) if map.edge(from, dir) == Passage::Open && map.threat(to).is_none() => from = to,
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    fn replan(&mut self, map: &WorldMap) {
+        self.plan.clear();
+        self.goal = None;
+        let (order, parents) = breadth_first(map);
+        let start = map.position();
+        let goal = match self.target(map).filter(|target| parents.contains_key(target)) {
+            Some(target) => Some(target),
+            None => {
+                // Une sortie aperçue mais pas encore accessible sert de boussole.
+                let heading = self.target(map).map(|target| bearing(start, target)).or(self.compass);
+                let cost = |pos: MapPos, distance: usize| {
+                    let deviation = heading.map_or(0.0, |h| angle_between(bearing(start, pos), h));
+                    distance as f32 + COMPASS_WEIGHT * deviation / 90.0
+                };
+                let best = |free: bool| {
+                    order.iter()
+                        .filter(|(pos, _)| *pos != start && is_frontier(map, *pos))
+                        .chain(order.first().filter(|(pos, _)| is_frontier(map, *pos)))
+                        .filter(|(pos, _)| !free || !self.is_claimed(*pos))
+                        .fold(None, |best: Option<(MapPos, f32)>, &(pos, distance)| {
+                            let c = cost(pos, distance);
+                            match best {
+                                Some((_, best_cost)) if best_cost <= c => best,
+                                _ => Some((pos, c)),
+                            }
+                        })
+                        .map(|(pos, _)| pos)
+                };
+                best(true).or_else(|| best(false))
+            }
+        };
+        let Some(goal) = goal else {
+            return;
+        };
+        let mut path = VecDeque::new();
+        let mut cursor = goal;
+        while cursor != start {
+            path.push_front(cursor);
+            cursor = parents[&cursor];
+        }
+        if path.is_empty() {
+            // Frontière sur place : il reste un passage inconnu à essayer.
+            if let Some(dir) = world_map::Direction::ALL.into_iter().find(|dir| {
+                let next = dir.step(start);
+                map.edge(start, *dir) == Passage::Undefined && map.contains(next) && map.threat(next).is_none()
+            }) {
+                path.push_back(dir.step(start));
             }
-            println!("La solution a été écrite dans maze_solution_pledge.txt");
         }
-        None => {
-            println!("Aucune solution trouvée avec l'algorithme Pledge pour ce labyrinthe.");
+        self.goal = Some(goal);
+        self.plan = path;
+    }
+}
+
+/// Une menace est juste de l'autre côté d'un passage ouvert de la cellule.
+fn threatened(map: &WorldMap, pos: MapPos) -> bool {
+    world_map::Direction::ALL.into_iter()
+        .any(|dir| map.edge(pos, dir) == Passage::Open && map.threat(dir.step(pos)).is_some())
+}
+
+/// Pas de côté vers une cellule libre qu'aucune menace ne touche, si une menace touche le joueur.
+fn retreat(map: &WorldMap) -> Option<RelativeDirection> {
+    let position = map.position();
+    if !threatened(map, position) {
+        return None;
+    }
+    let dir = world_map::Direction::ALL.into_iter().find(|dir| {
+        let next = dir.step(position);
+        map.edge(position, *dir) == Passage::Open
+            && map.contains(next)
+            && map.threat(next).is_none()
+            && !threatened(map, next)
+    })?;
+    Some(map.facing().relative_to(dir))
+}
+
+/// Premier déplacement, relatif à l'orientation du joueur, du plus court chemin connu vers `goal`.
+pub fn step_towards(map: &WorldMap, goal: MapPos) -> Option<RelativeDirection> {
+    let (_, parents) = breadth_first(map);
+    let start = map.position();
+    let mut cursor = goal;
+    let mut next = None;
+    while cursor != start {
+        next = Some(cursor);
+        cursor = *parents.get(&cursor)?;
+    }
+    let next = next?;
+    let dir = world_map::Direction::ALL.into_iter().find(|dir| dir.step(start) == next)?;
+    Some(map.facing().relative_to(dir))
+}
+
+/// La cellule borde l'inconnu : un passage indéterminé ou un passage ouvert vers une cellule jamais vue.
+fn is_frontier(map: &WorldMap, pos: MapPos) -> bool {
+    world_map::Direction::ALL.into_iter().any(|dir| {
+        let next = dir.step(pos);
+        map.contains(next)
+            && match map.edge(pos, dir) {
+                Passage::Undefined => true,
+                Passage::Open => map.cell(next) == world_map::CellState::Unknown,
+                Passage::Wall => false,
+            }
+    })
+}
+
+fn reachable(map: &WorldMap, pos: MapPos) -> bool {
+    breadth_first(map).1.contains_key(&pos)
+}
+
+/// Parcours en largeur depuis le joueur sur les passages ouverts connus, sans entrer sur une
+/// cellule menacée. Renvoie les cellules atteintes avec leur distance, dans l'ordre de visite,
+/// et le parent de chacune.
+fn breadth_first(map: &WorldMap) -> (Vec<(MapPos, usize)>, HashMap<MapPos, MapPos>) {
+    let start = map.position();
+    let mut parents = HashMap::new();
+    parents.insert(start, start);
+    let mut order = Vec::new();
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((pos, distance)) = queue.pop_front() {
+        order.push((pos, distance));
+        for dir in world_map::Direction::ALL {
+            let next = dir.step(pos);
+            if map.edge(pos, dir) == Passage::Open
+                && map.contains(next)
+                && map.threat(next).is_none()
+                && !parents.contains_key(&next)
+            {
+                parents.insert(next, pos);
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    (order, parents)
+}
+
+/// Chemin trouvé hors ligne. `steps` compte les cellules explorées par une recherche,
+/// ou les déplacements d'un algorithme qui marche dans le labyrinthe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub path: Vec<Position>,
+    pub steps: usize,
+}
+
+/// Algorithme de résolution, utilisable hors ligne sur un labyrinthe connu
+/// ou en ligne, un déplacement à la fois, sur la carte reconstruite par le joueur.
+pub trait MazeSolver: Send {
+    fn name(&self) -> &'static str;
+
+    /// Chemin de `start` à `exit` en ne franchissant que des passages connus comme ouverts.
+    fn solve(&self, maze: &Maze, start: Position, exit: Position) -> Option<Solution>;
+
+    /// Prochain déplacement, relatif à l'orientation du joueur, d'après la carte courante.
+    fn next_move(&mut self, map: &WorldMap) -> Option<RelativeDirection>;
+
+    /// Enregistre un `Hint::RelativeCompass` reçu alors que le joueur regardait vers `facing`.
+    fn observe_compass(&mut self, _angle: f32, _facing: world_map::Direction) {}
+}
+
+/// Noms acceptés par [`solver_by_name`].
+pub const SOLVERS: [&str; 7] = ["astar", "bfs", "dijkstra", "tremaux", "right-hand", "left-hand", "pledge"];
+
+pub fn solver_by_name(name: &str) -> Option<Box<dyn MazeSolver>> {
+    match name {
+        "astar" => Some(Box::new(AStar::default())),
+        "bfs" => Some(Box::new(Bfs::default())),
+        "dijkstra" => Some(Box::new(Dijkstra::default())),
+        "tremaux" => Some(Box::new(Tremaux::default())),
+        "right-hand" => Some(Box::new(WallFollower { hand: Hand::Right })),
+        "left-hand" => Some(Box::new(WallFollower { hand: Hand::Left })),
+        "pledge" => Some(Box::new(OnlinePledge::default())),
+        _ => None,
+    }
+}
+
+fn neighbours(maze: &Maze, pos: Position) -> impl Iterator<Item = Position> + '_ {
+    Direction::ALL.into_iter().filter_map(move |dir| maze.step(pos, dir))
+}
+
+fn rebuild_path(parents: &HashMap<Position, Position>, start: Position, exit: Position) -> Vec<Position> {
+    let mut path = vec![exit];
+    let mut cursor = exit;
+    while cursor != start {
+        cursor = parents[&cursor];
+        path.push(cursor);
+    }
+    path.reverse();
+    path
+}
+
+/// Recherche du plus court chemin ; avec `heuristic`, c'est un A* (distance de Manhattan).
+fn shortest_path(maze: &Maze, start: Position, exit: Position, heuristic: bool) -> Option<Solution> {
+    if !maze.contains(start) {
+        return None;
+    }
+    let h = |(i, j): Position| if heuristic { i.abs_diff(exit.0) + j.abs_diff(exit.1) } else { 0 };
+    let mut costs = HashMap::from([(start, 0usize)]);
+    let mut parents = HashMap::new();
+    let mut closed = HashSet::new();
+    let mut heap = BinaryHeap::from([Reverse((h(start), start))]);
+    while let Some(Reverse((_, pos))) = heap.pop() {
+        if !closed.insert(pos) {
+            continue;
+        }
+        if pos == exit {
+            return Some(Solution { path: rebuild_path(&parents, start, exit), steps: closed.len() });
         }
+        let cost = costs[&pos] + 1;
+        for next in neighbours(maze, pos) {
+            if costs.get(&next).is_none_or(|&known| cost < known) {
+                costs.insert(next, cost);
+                parents.insert(next, pos);
+                heap.push(Reverse((cost + h(next), next)));
+            }
+        }
+    }
+    None
+}
+
+/// Parcours en largeur : plus court chemin. Les trois recherches (`Bfs`, `Dijkstra`, `AStar`)
+/// ne diffèrent que hors ligne : en ligne, elles explorent toutes avec [`FrontierPlanner`],
+/// guidé par la boussole, et sont proposées aux joueurs sous le seul nom `frontier`.
+#[derive(Debug, Default)]
+pub struct Bfs {
+    planner: FrontierPlanner,
+}
+
+impl MazeSolver for Bfs {
+    fn name(&self) -> &'static str {
+        "bfs"
+    }
+
+    fn solve(&self, maze: &Maze, start: Position, exit: Position) -> Option<Solution> {
+        if !maze.contains(start) {
+            return None;
+        }
+        let mut parents = HashMap::from([(start, start)]);
+        let mut queue = VecDeque::from([start]);
+        let mut steps = 0;
+        while let Some(pos) = queue.pop_front() {
+            steps += 1;
+            if pos == exit {
+                return Some(Solution { path: rebuild_path(&parents, start, exit), steps });
+            }
+            for next in neighbours(maze, pos) {
+                if let std::collections::hash_map::Entry::Vacant(entry) = parents.entry(next) {
+                    entry.insert(pos);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    fn next_move(&mut self, map: &WorldMap) -> Option<RelativeDirection> {
+        self.planner.next_move(map)
+    }
+
+    fn observe_compass(&mut self, angle: f32, facing: world_map::Direction) {
+        self.planner.observe_compass(angle, facing);
     }
 }
 
+/// Dijkstra à coût uniforme : mêmes chemins que le parcours en largeur, avec une file de priorité.
+#[derive(Debug, Default)]
+pub struct Dijkstra {
+    planner: FrontierPlanner,
+}
+
+impl MazeSolver for Dijkstra {
+    fn name(&self) -> &'static str {
+        "dijkstra"
+    }
+
+    fn solve(&self, maze: &Maze, start: Position, exit: Position) -> Option<Solution> {
+        shortest_path(maze, start, exit, false)
+    }
+
+    fn next_move(&mut self, map: &WorldMap) -> Option<RelativeDirection> {
+        self.planner.next_move(map)
+    }
+
+    fn observe_compass(&mut self, angle: f32, facing: world_map::Direction) {
+        self.planner.observe_compass(angle, facing);
+    }
+}
+
+/// A* guidé par la distance de Manhattan. En ligne, même exploration que `Bfs`.
+#[derive(Debug, Default)]
+pub struct AStar {
+    planner: FrontierPlanner,
+}
+
+impl MazeSolver for AStar {
+    fn name(&self) -> &'static str {
+        "astar"
+    }
+
+    fn solve(&self, maze: &Maze, start: Position, exit: Position) -> Option<Solution> {
+        shortest_path(maze, start, exit, true)
+    }
+
+    fn next_move(&mut self, map: &WorldMap) -> Option<RelativeDirection> {
+        self.planner.next_move(map)
+    }
+
+    fn observe_compass(&mut self, angle: f32, facing: world_map::Direction) {
+        self.planner.observe_compass(angle, facing);
+    }
+}
+
+/// Passage ouvert depuis `pos` dans la direction `dir`, dans le repère de la carte.
+type OpenFn<'a> = &'a dyn Fn(MapPos, world_map::Direction) -> bool;
+
+/// Direction du labyrinthe correspondant à une direction de la carte.
+fn absolute(dir: world_map::Direction) -> Direction {
+    match dir {
+        world_map::Direction::North => Direction::North,
+        world_map::Direction::East => Direction::East,
+        world_map::Direction::South => Direction::South,
+        world_map::Direction::West => Direction::West,
+    }
+}
+
+fn maze_open(maze: &Maze) -> impl Fn(MapPos, world_map::Direction) -> bool + '_ {
+    move |(r, c), dir| r >= 0 && c >= 0 && maze.step((r as usize, c as usize), absolute(dir)).is_some()
+}
+
+fn map_open(map: &WorldMap) -> impl Fn(MapPos, world_map::Direction) -> bool + '_ {
+    move |pos, dir| map.edge(pos, dir) == Passage::Open && map.contains(dir.step(pos))
+}
+
+/// Fait marcher un algorithme pas à pas dans un labyrinthe connu, en partant vers le nord.
+fn walk<F>(maze: &Maze, start: Position, exit: Position, mut step: F) -> Option<Solution>
+where
+    F: FnMut(OpenFn, MapPos, world_map::Direction) -> Option<world_map::Direction>,
+{
+    if !maze.contains(start) {
+        return None;
+    }
+    let open = maze_open(maze);
+    let mut pos = (start.0 as i64, start.1 as i64);
+    let mut facing = world_map::Direction::North;
+    let mut path = vec![start];
+    let limit = maze.rows() * maze.columns() * 20;
+    while path.last() != Some(&exit) {
+        if path.len() > limit {
+            return None;
+        }
+        facing = step(&open, pos, facing)?;
+        pos = facing.step(pos);
+        path.push((pos.0 as usize, pos.1 as usize));
+    }
+    let steps = path.len() - 1;
+    Some(Solution { path, steps })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// Suit un mur en gardant toujours la même main contre lui.
+/// Sort des labyrinthes parfaits, mais peut tourner en rond autour d'un îlot.
+#[derive(Debug, Clone, Copy)]
+pub struct WallFollower {
+    pub hand: Hand,
+}
+
+impl WallFollower {
+    fn step(&self, open: OpenFn, pos: MapPos, facing: world_map::Direction) -> Option<world_map::Direction> {
+        let order = match self.hand {
+            Hand::Right => [facing.turn_right(), facing, facing.turn_left(), facing.opposite()],
+            Hand::Left => [facing.turn_left(), facing, facing.turn_right(), facing.opposite()],
+        };
+        order.into_iter().find(|dir| open(pos, *dir))
+    }
+}
+
+impl MazeSolver for WallFollower {
+    fn name(&self) -> &'static str {
+        match self.hand {
+            Hand::Right => "right-hand",
+            Hand::Left => "left-hand",
+        }
+    }
+
+    fn solve(&self, maze: &Maze, start: Position, exit: Position) -> Option<Solution> {
+        walk(maze, start, exit, |open, pos, facing| self.step(open, pos, facing))
+    }
+
+    fn next_move(&mut self, map: &WorldMap) -> Option<RelativeDirection> {
+        let dir = self.step(&map_open(map), map.position(), map.facing())?;
+        Some(map.facing().relative_to(dir))
+    }
+}
+
+/// Clé d'un passage, identique quel que soit le côté d'où on le regarde.
+fn passage_key(pos: MapPos, dir: world_map::Direction) -> (MapPos, bool) {
+    match dir {
+        world_map::Direction::North => (pos, true),
+        world_map::Direction::West => (pos, false),
+        world_map::Direction::South => (dir.step(pos), true),
+        world_map::Direction::East => (dir.step(pos), false),
+    }
+}
+
+/// Marques de l'algorithme de Trémaux : chaque passage est emprunté au plus deux fois.
+#[derive(Debug, Clone, Default)]
+struct TremauxMarks {
+    marks: HashMap<(MapPos, bool), u8>,
+    visited: HashSet<MapPos>,
+    /// Direction pour revenir par le passage d'arrivée.
+    back: Option<world_map::Direction>,
+}
+
+impl TremauxMarks {
+    fn marks(&self, pos: MapPos, dir: world_map::Direction) -> u8 {
+        self.marks.get(&passage_key(pos, dir)).copied().unwrap_or(0)
+    }
+
+    fn decide(&self, open: OpenFn, pos: MapPos) -> Option<world_map::Direction> {
+        let options: Vec<_> = world_map::Direction::ALL.into_iter()
+            .filter(|dir| open(pos, *dir) && self.marks(pos, *dir) < 2)
+            .collect();
+        // Arrivée par un nouveau passage sur une cellule déjà connue : on fait demi-tour.
+        if let Some(back) = self.back {
+            if self.visited.contains(&pos) && self.marks(pos, back) == 1 && options.contains(&back) {
+                return Some(back);
+            }
+        }
+        options.iter()
+            .filter(|dir| Some(**dir) != self.back)
+            .min_by_key(|dir| self.marks(pos, **dir))
+            .or_else(|| options.first())
+            .copied()
+    }
+
+    fn commit(&mut self, from: MapPos, dir: world_map::Direction) {
+        *self.marks.entry(passage_key(from, dir)).or_insert(0) += 1;
+        self.visited.insert(from);
+        self.back = Some(dir.opposite());
+    }
+}
+
+/// Algorithme de Trémaux : marque les passages empruntés et garantit la sortie de tout labyrinthe.
+#[derive(Debug, Default)]
+pub struct Tremaux {
+    marks: TremauxMarks,
+    /// Dernier déplacement proposé, pris en compte une fois accepté.
+    pending: Option<(MapPos, world_map::Direction)>,
+}
+
+impl MazeSolver for Tremaux {
+    fn name(&self) -> &'static str {
+        "tremaux"
+    }
+
+    fn solve(&self, maze: &Maze, start: Position, exit: Position) -> Option<Solution> {
+        let mut marks = TremauxMarks::default();
+        walk(maze, start, exit, |open, pos, _| {
+            let dir = marks.decide(open, pos)?;
+            marks.commit(pos, dir);
+            Some(dir)
+        })
+    }
+
+    fn next_move(&mut self, map: &WorldMap) -> Option<RelativeDirection> {
+        let position = map.position();
+        if let Some((from, dir)) = self.pending.take() {
+            if dir.step(from) == position {
+                self.marks.commit(from, dir);
+            }
+        }
+        let dir = self.marks.decide(&map_open(map), position)?;
+        self.pending = Some((position, dir));
+        Some(map.facing().relative_to(dir))
+    }
+}
+
+/// Direction de la carte la plus proche d'un cap en degrés.
+fn nearest_direction(bearing: f32) -> world_map::Direction {
+    world_map::Direction::ALL[((bearing.rem_euclid(360.0) + 45.0) / 90.0) as usize % 4]
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PledgeState {
+    current: world_map::Direction,
+    counter: i32,
+    in_pledge: bool,
+}
+
+impl PledgeState {
+    fn step(&mut self, open: OpenFn, pos: MapPos, goal: world_map::Direction) -> Option<world_map::Direction> {
+        if self.in_pledge && self.counter == 0 && open(pos, goal) {
+            self.in_pledge = false;
+        }
+        if !self.in_pledge {
+            if open(pos, goal) {
+                self.current = goal;
+                return Some(goal);
+            }
+            self.in_pledge = true;
+            self.current = goal.turn_left();
+            self.counter = -1;
+        }
+        for _ in 0..4 {
+            let right = self.current.turn_right();
+            if open(pos, right) {
+                self.current = right;
+                self.counter += 1;
+                return Some(right);
+            }
+            if open(pos, self.current) {
+                return Some(self.current);
+            }
+            self.current = self.current.turn_left();
+            self.counter -= 1;
+        }
+        None
+    }
+}
+
+/// Pledge : avance vers une direction privilégiée (la boussole si elle est connue)
+/// et contourne les obstacles main droite au mur en comptant les virages.
+#[derive(Debug, Default)]
+pub struct OnlinePledge {
+    goal: Option<world_map::Direction>,
+    state: Option<PledgeState>,
+    /// État proposé avec le dernier déplacement, adopté si le joueur a bougé.
+    pending: Option<(MapPos, PledgeState)>,
+}
+
+impl MazeSolver for OnlinePledge {
+    fn name(&self) -> &'static str {
+        "pledge"
+    }
+
+    fn solve(&self, maze: &Maze, start: Position, exit: Position) -> Option<Solution> {
+        // Direction privilégiée : l'axe principal vers la sortie.
+        let (di, dj) = (exit.0 as i64 - start.0 as i64, exit.1 as i64 - start.1 as i64);
+        let goal = if di.abs() >= dj.abs() {
+            if di >= 0 { Direction::South } else { Direction::North }
+        } else if dj >= 0 {
+            Direction::East
+        } else {
+            Direction::West
+        };
+        let path = solve_maze_pledge(maze, start, exit, goal)?;
+        let steps = path.len() - 1;
+        Some(Solution { path, steps })
+    }
+
+    fn next_move(&mut self, map: &WorldMap) -> Option<RelativeDirection> {
+        let position = map.position();
+        if let Some((from, state)) = self.pending.take() {
+            if from != position {
+                self.state = Some(state);
+            }
+        }
+        let goal = *self.goal.get_or_insert(map.facing());
+        let mut state = self.state.unwrap_or(PledgeState { current: goal, counter: 0, in_pledge: false });
+        let dir = state.step(&map_open(map), position, goal)?;
+        self.pending = Some((position, state));
+        Some(map.facing().relative_to(dir))
+    }
+
+    fn observe_compass(&mut self, angle: f32, facing: world_map::Direction) {
+        // Changer de cap en plein contournement fausserait le compteur de virages.
+        if !self.state.is_some_and(|state| state.in_pledge) {
+            self.goal = Some(nearest_direction(heading_degrees(facing) + angle));
+        }
+    }
+}
+
+/// Résultat d'un solveur dans une comparaison.
+#[derive(Debug, Clone)]
+pub struct SolverReport {
+    pub name: &'static str,
+    pub solution: Option<Solution>,
+}
+
+impl SolverReport {
+    /// Nombre de déplacements du chemin trouvé.
+    pub fn path_length(&self) -> Option<usize> {
+        self.solution.as_ref().map(|solution| solution.path.len() - 1)
+    }
+}
+
+/// Fait résoudre le même labyrinthe à tous les solveurs de [`SOLVERS`].
+pub fn compare(maze: &Maze, start: Position, exit: Position) -> Vec<SolverReport> {
+    SOLVERS.iter()
+        .filter_map(|name| solver_by_name(name))
+        .map(|solver| SolverReport { name: solver.name(), solution: solver.solve(maze, start, exit) })
+        .collect()
+}
+
+/// Tableau de comparaisons étiquetées : une ligne par labyrinthe et par solveur, avec la longueur
+/// du chemin trouvé et le nombre d'étapes (0 sans chemin).
+pub fn comparison_table(comparisons: &[(String, Vec<SolverReport>)]) -> String {
+    let mut lines = vec![format!("{:<16} {:<12} {:>8} {:>8}", "labyrinthe", "solveur", "chemin", "étapes")];
+    for (label, reports) in comparisons {
+        for report in reports {
+            let (length, steps) = report.solution.as_ref().map_or((0, 0), |s| (s.path.len() - 1, s.steps));
+            lines.push(format!("{:<16} {:<12} {:>8} {:>8}", label, report.name, length, steps));
+        }
+    }
+    lines.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     
     #[test]
     fn test_solve_maze_pledge() {
-        let grid = vec![
-            vec![false, false, false, false, false],
-            vec![false, true,  true,  true,  false],
-            vec![false, true,  false, true,  false],
-            vec![false, true,  true,  true,  false],
-            vec![false, false, false, false, false],
-        ];
-        let start = (1, 1);
-        let exit = (3, 3);
+        // Quatre cellules ouvertes autour d'un pilier central.
+        let maze = server::maze::Maze::open(2, 2).edges();
+        let start = (0, 0);
+        let exit = (1, 1);
         let goal = Direction::East;
-        let path = solve_maze_pledge(&grid, start, exit, goal);
+        let path = solve_maze_pledge(&maze, start, exit, goal);
         assert!(path.is_some());
     }
+
+    #[test]
+    fn test_pledge_keeps_the_wall_on_its_right() {
+        // Un virage à droite devant le mur ramènerait le joueur en bas du U, face au même mur,
+        // avec un compteur revenu à zéro : il y tournerait en rond.
+        let maze = server::maze::Maze::from_ascii(
+            "
+            +-+-+
+            | |E|
+            + + +
+            |   |
+            +-+-+
+            ",
+        )
+        .edges();
+        let path = solve_maze_pledge(&maze, (0, 0), (0, 1), Direction::East);
+        assert_eq!(path, Some(vec![(0, 0), (1, 0), (1, 1), (0, 1)]));
+    }
+
+    /// Fait explorer un labyrinthe généré par le serveur au planificateur, avec une boussole
+    /// tous les `compass_every` déplacements, et renvoie le nombre de déplacements jusqu'à la sortie.
+    fn explore(
+        solver: &mut dyn MazeSolver,
+        maze: &server::maze::Maze,
+        start: server::maze::Position,
+        facing: server::maze::Direction,
+        compass_every: usize,
+    ) -> Option<usize> {
+        use server::radar::{compute_radar, Entities};
+
+        let entities = Entities { exit: Some(maze.exit()), ..Entities::default() };
+        let (mut position, mut facing) = (start, facing);
+        let mut map = WorldMap::new();
+        let limit = 4 * maze.rows() * maze.columns();
+        for moves in 0..limit {
+            if position == maze.exit() {
+                return Some(moves);
+            }
+            map.observe(&compute_radar(maze, position, facing, &entities));
+            if compass_every > 0 && moves % compass_every == 0 {
+                let angle = server::hints::relative_compass(position, facing, maze.exit());
+                solver.observe_compass(angle, map.facing());
+            }
+            let dir = solver.next_move(&map)?;
+            let heading = facing.turn(&dir);
+            position = maze.step(position, heading).expect("le solveur ne traverse pas les murs");
+            facing = heading;
+            map.apply_move(&dir);
+        }
+        None
+    }
+
+    #[test]
+    fn test_frontier_planner_reaches_exit_of_generated_mazes() {
+        use server::maze::generators::Algorithm;
+        use server::maze::Direction as ServerDirection;
+
+        for algorithm in Algorithm::ALL {
+            for seed in 0..6u64 {
+                let (width, height) = [(6, 4), (11, 7), (16, 10)][seed as usize % 3];
+                let maze = algorithm.generate(width, height, seed);
+                let start = ((seed as usize * 7) % height, (seed as usize * 5) % width);
+                let facing = ServerDirection::ALL[seed as usize % 4];
+                for compass_every in [0, 5] {
+                    assert!(
+                        explore(&mut AStar::default(), &maze, start, facing, compass_every).is_some(),
+                        "{} graine {} depuis {:?}, boussole {}:\n{}",
+                        algorithm, seed, start, compass_every, maze.to_ascii()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_compass_guides_exploration() {
+        // Dans un labyrinthe ouvert, la boussole mène droit vers la sortie, quelle que soit la recherche.
+        let maze = server::maze::Maze::open(9, 9);
+        for name in ["astar", "bfs", "dijkstra"] {
+            let mut solver = solver_by_name(name).unwrap();
+            let guided = explore(solver.as_mut(), &maze, (0, 0), server::maze::Direction::North, 1).unwrap();
+            assert_eq!(guided, 16, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_planner_avoids_threats_or_waits() {
+        use server::radar::{compute_radar, Entities};
+        use server::maze::Direction as ServerDirection;
+
+        // La sortie est en avant à gauche ; un monstre bloque le chemin par l'avant.
+        let mut maze = server::maze::Maze::open(3, 3);
+        maze.set_exit((0, 0));
+        let view = |monsters: Vec<Position>| {
+            let entities = Entities { exit: Some((0, 0)), monsters, ..Entities::default() };
+            compute_radar(&maze, (1, 1), ServerDirection::North, &entities)
+        };
+        let mut map = WorldMap::new();
+        map.observe(&view(vec![]));
+        assert_eq!(FrontierPlanner::new().next_move(&map), Some(RelativeDirection::Front));
+        map.observe(&view(vec![(0, 1)]));
+        assert_eq!(FrontierPlanner::new().next_move(&map), Some(RelativeDirection::Left));
+
+        // Dans un couloir, il n'y a pas d'autre chemin : le joueur laisse de la place à la menace,
+        // puis attend qu'elle s'estompe.
+        let corridor = server::maze::Maze::from_ascii(
+            "
+            +-+-+-+-+
+            |      E|
+            +-+-+-+-+
+            ",
+        );
+        let entities = Entities { exit: Some(corridor.exit()), monsters: vec![(0, 2)], ..Entities::default() };
+        let mut map = WorldMap::new();
+        map.observe(&compute_radar(&corridor, (0, 1), ServerDirection::East, &entities));
+        assert_eq!(FrontierPlanner::new().next_move(&map), Some(RelativeDirection::Back));
+        let entities = Entities { monsters: vec![(0, 1)], ..entities };
+        let mut map = WorldMap::new();
+        map.observe(&compute_radar(&corridor, (0, 0), ServerDirection::East, &entities));
+        let mut planner = FrontierPlanner::new();
+        assert_eq!(planner.next_move(&map), None);
+        for _ in 0..world_map::THREAT_DECAY {
+            map.tick();
+        }
+        assert_eq!(planner.next_move(&map), Some(RelativeDirection::Front));
+    }
+
+    #[test]
+    fn test_next_move_is_relative_to_facing() {
+        let mut map = WorldMap::new();
+        map.observe(&corridor_view());
+        let mut planner = FrontierPlanner::new();
+        assert_eq!(planner.next_move(&map), Some(RelativeDirection::Right));
+    }
+
+    /// Vue d'un joueur dans un couloir qui ne s'ouvre que sur sa droite.
+    fn corridor_view() -> shared::radar::RadarGrid {
+        let mut grid = shared::radar::RadarGrid::default();
+        for row in 0..4 {
+            for col in 0..3 {
+                grid.set_horizontal(row, col, Passage::Wall);
+            }
+        }
+        for row in 0..3 {
+            for col in 0..4 {
+                grid.set_vertical(row, col, Passage::Wall);
+            }
+        }
+        grid.set_vertical(1, 2, Passage::Open);
+        grid.set_vertical(1, 3, Passage::Open);
+        grid
+    }
+
+    fn assert_valid_path(maze: &Maze, path: &[Position]) {
+        for pair in path.windows(2) {
+            assert!(
+                Direction::ALL.into_iter().any(|dir| maze.step(pair[0], dir) == Some(pair[1])),
+                "{:?}", pair
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare_solvers_on_generated_mazes() {
+        use server::maze::generators::Algorithm;
+
+        let mut comparisons = Vec::new();
+        for algorithm in Algorithm::ALL {
+            for seed in 0..4u64 {
+                let maze = algorithm.generate(12, 8, seed);
+                let edges = maze.edges();
+                let start = (0, 0);
+                let exit = maze.exit();
+                let reports = compare(&edges, start, exit);
+                assert_eq!(reports.len(), SOLVERS.len());
+                for report in &reports {
+                    if let Some(solution) = &report.solution {
+                        assert_eq!(solution.path.first(), Some(&start));
+                        assert_eq!(solution.path.last(), Some(&exit));
+                        assert_valid_path(&edges, &solution.path);
+                    }
+                }
+
+                let length = |name: &str| reports.iter().find(|r| r.name == name).and_then(SolverReport::path_length);
+                let shortest = length("bfs").expect("la sortie est accessible");
+                assert_eq!(length("dijkstra"), Some(shortest));
+                assert_eq!(length("astar"), Some(shortest));
+                assert!(length("tremaux").is_some_and(|l| l >= shortest));
+                if algorithm != Algorithm::Braided {
+                    // Dans un labyrinthe parfait, suivre un mur mène partout.
+                    assert!(length("right-hand").is_some(), "{} {}", algorithm, seed);
+                    assert!(length("left-hand").is_some(), "{} {}", algorithm, seed);
+                }
+                comparisons.push((format!("{} {}", algorithm, seed), reports));
+            }
+        }
+        let table = comparison_table(&comparisons);
+        assert_eq!(table.lines().count(), 1 + Algorithm::ALL.len() * 4 * SOLVERS.len());
+    }
+
+    #[test]
+    fn test_solvers_only_cross_known_passages() {
+        let mut maze = Maze::new(1, 3);
+        maze.set_passage((0, 0), Direction::East, Passage::Open);
+        for name in SOLVERS {
+            let solver = solver_by_name(name).unwrap();
+            assert!(solver.solve(&maze, (0, 0), (0, 2)).is_none(), "{}", name);
+        }
+        maze.set_passage((0, 1), Direction::East, Passage::Open);
+        let solution = Bfs::default().solve(&maze, (0, 0), (0, 2)).unwrap();
+        assert_eq!(solution.path, vec![(0, 0), (0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn test_online_solvers_reach_exit() {
+        use server::maze::generators::Algorithm;
+        use server::maze::Direction as ServerDirection;
+
+        for algorithm in Algorithm::ALL {
+            let maze = algorithm.generate(11, 7, 2);
+            for name in ["bfs", "dijkstra", "tremaux"] {
+                let mut solver = solver_by_name(name).unwrap();
+                assert!(explore(solver.as_mut(), &maze, (0, 0), ServerDirection::East, 0).is_some(), "{} {}", name, algorithm);
+            }
+            if algorithm != Algorithm::Braided {
+                for name in ["right-hand", "left-hand"] {
+                    let mut solver = solver_by_name(name).unwrap();
+                    assert!(explore(solver.as_mut(), &maze, (0, 0), ServerDirection::East, 0).is_some(), "{} {}", name, algorithm);
+                }
+            }
+        }
+        // Pledge suit la boussole à travers un labyrinthe ouvert.
+        let maze = server::maze::Maze::open(9, 9);
+        let mut pledge = solver_by_name("pledge").unwrap();
+        assert!(explore(pledge.as_mut(), &maze, (0, 0), ServerDirection::North, 1).is_some());
+    }
 }
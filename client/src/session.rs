@@ -0,0 +1,208 @@
+use std::net::Shutdown;
+
+use crate::error::ClientError;
+use crate::network::{self, Connection};
+use shared::messages::{
+    Action,
+    ActionError,
+    Challenge,
+    Hint,
+    Message,
+    SubscribePlayer,
+    SubscribePlayerResult,
+};
+use shared::radar::RadarGrid;
+
+/// Commandes envoyées par l'interface au thread qui possède la session.
+#[derive(Debug)]
+pub enum SessionCommand {
+    Action(Action),
+    /// Active le pilote automatique avec la stratégie nommée, ou le coupe avec `None`.
+    Autopilot(Option<String>),
+    Shutdown,
+}
+
+/// Messages reçus du serveur pendant la vie de la session.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionEvent {
+    RadarView(String),
+    Hint(Hint),
+    Challenge(Challenge),
+    ActionError(ActionError),
+}
+
+/// Session d'un joueur : une seule connexion, ouverte à l'inscription et conservée
+/// jusqu'à la fin de la partie, sur laquelle passent toutes ses actions.
+pub struct PlayerSession {
+    pub player_name: String,
+    stream: Connection,
+}
+
+impl PlayerSession {
+    /// Ouvre la connexion du joueur, l'inscrit avec le token d'équipe et lit les messages
+    /// jusqu'à sa première RadarView, qui est toujours le dernier événement renvoyé.
+    /// Cette vue est décodée : une session n'est rendue qu'avec un radar valide.
+    pub fn subscribe(
+        server_address: &str,
+        token: &str,
+        player_name: &str,
+    ) -> Result<(Self, RadarGrid, Vec<SessionEvent>), ClientError> {
+        let mut stream = network::connect(server_address)?;
+        stream.send(&Message::SubscribePlayer(SubscribePlayer {
+            name: player_name.to_string(),
+            registration_token: token.to_string(),
+        }))?;
+        match stream.recv()? {
+            Message::SubscribePlayerResult(SubscribePlayerResult::Ok) => {}
+            Message::SubscribePlayerResult(SubscribePlayerResult::Err(reason)) => {
+                return Err(ClientError::SubscribePlayer(reason));
+            }
+            other => return Err(ClientError::Unexpected(other)),
+        }
+        let mut session = PlayerSession {
+            player_name: player_name.to_string(),
+            stream,
+        };
+        let events = session.read_response()?;
+        let radar = match events.last() {
+            Some(SessionEvent::RadarView(encoded)) => RadarGrid::decode(encoded).map_err(ClientError::InvalidRadar)?,
+            Some(SessionEvent::ActionError(err)) => return Err(ClientError::Unexpected(Message::ActionError(err.clone()))),
+            _ => unreachable!("read_response se termine par une RadarView ou une ActionError"),
+        };
+        Ok((session, radar, events))
+    }
+
+    /// Envoie une action et lit les messages du serveur jusqu'à sa réponse
+    /// (une RadarView si l'action est acceptée, une ActionError sinon).
+    /// Les indices et challenges reçus entre-temps sont renvoyés dans l'ordre d'arrivée.
+    pub fn perform(&mut self, action: Action) -> Result<Vec<SessionEvent>, ClientError> {
+        self.stream.send(&Message::Action(action))?;
+        self.read_response()
+    }
+
+    /// Lit les messages jusqu'à une RadarView ou une ActionError incluse.
+    fn read_response(&mut self) -> Result<Vec<SessionEvent>, ClientError> {
+        let mut events = Vec::new();
+        loop {
+            let event = match self.stream.recv()? {
+                Message::RadarView(rv) => SessionEvent::RadarView(rv.0),
+                Message::ActionError(err) => SessionEvent::ActionError(err),
+                Message::Hint(hint) => SessionEvent::Hint(hint),
+                Message::Challenge(challenge) => SessionEvent::Challenge(challenge),
+                other => return Err(ClientError::Unexpected(other)),
+            };
+            let done = matches!(event, SessionEvent::RadarView(_) | SessionEvent::ActionError(_));
+            events.push(event);
+            if done {
+                return Ok(events);
+            }
+        }
+    }
+
+    /// Ferme proprement la connexion du joueur.
+    pub fn shutdown(self) -> Result<(), ClientError> {
+        self.stream.get_ref().shutdown(Shutdown::Both)?;
+        Ok(())
+    }
+}
+
+/// Résultat d'un déplacement : la première réponse terminale de la série d'événements
+/// (les réponses au challenge éventuel viennent après).
+pub fn move_outcome(events: &[SessionEvent]) -> Option<Result<(), &ActionError>> {
+    events.iter().find_map(|event| match event {
+        SessionEvent::RadarView(_) => Some(Ok(())),
+        SessionEvent::ActionError(error) => Some(Err(error)),
+        _ => None,
+    })
+}
+
+/// Dernière vue radar de la série d'événements.
+pub fn last_radar(events: &[SessionEvent]) -> Option<RadarGrid> {
+    events.iter().rev().find_map(|event| match event {
+        SessionEvent::RadarView(encoded) => RadarGrid::decode(encoded).ok(),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::messages::{RadarView, RelativeDirection};
+    use shared::transport::{FramedStream, TransportError};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Vue radar valide, tirée de l'exemple du protocole.
+    const INITIAL: &str = "ieysGjGO8papd/a";
+
+    /// Lance un serveur qui joue un scénario fixe sur une seule connexion.
+    fn scripted_server<F>(script: F) -> (String, thread::JoinHandle<()>)
+    where
+        F: FnOnce(&mut FramedStream<std::net::TcpStream>) + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut framed = FramedStream::new(stream);
+            script(&mut framed);
+        });
+        (address, handle)
+    }
+
+    #[test]
+    fn test_session_uses_single_connection() {
+        let (address, server) = scripted_server(|s| {
+            assert!(matches!(s.recv().unwrap(), Message::SubscribePlayer(_)));
+            s.send(&Message::SubscribePlayerResult(SubscribePlayerResult::Ok)).unwrap();
+            s.send(&Message::Hint(Hint::Secret(3))).unwrap();
+            s.send(&Message::RadarView(RadarView(INITIAL.into()))).unwrap();
+            assert_eq!(s.recv().unwrap(), Message::Action(Action::MoveTo(RelativeDirection::Front)));
+            s.send(&Message::Hint(Hint::Secret(7))).unwrap();
+            s.send(&Message::RadarView(RadarView("apres".into()))).unwrap();
+            assert_eq!(s.recv().unwrap(), Message::Action(Action::MoveTo(RelativeDirection::Left)));
+            s.send(&Message::ActionError(ActionError::CannotPassThroughWall)).unwrap();
+            assert!(matches!(s.recv(), Err(TransportError::Closed)));
+        });
+
+        let (mut session, radar, events) = PlayerSession::subscribe(&address, "TOKEN", "alice").unwrap();
+        assert_eq!(radar, RadarGrid::decode(INITIAL).unwrap());
+        assert_eq!(events, vec![SessionEvent::Hint(Hint::Secret(3)), SessionEvent::RadarView(INITIAL.into())]);
+        let events = session.perform(Action::MoveTo(RelativeDirection::Front)).unwrap();
+        assert_eq!(events, vec![SessionEvent::Hint(Hint::Secret(7)), SessionEvent::RadarView("apres".into())]);
+        let events = session.perform(Action::MoveTo(RelativeDirection::Left)).unwrap();
+        assert_eq!(events, vec![SessionEvent::ActionError(ActionError::CannotPassThroughWall)]);
+        session.shutdown().unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_subscribe_rejected() {
+        let (address, server) = scripted_server(|s| {
+            s.recv().unwrap();
+            s.send(&Message::SubscribePlayerResult(SubscribePlayerResult::Err("InvalidRegistrationToken".into())))
+                .unwrap();
+        });
+        match PlayerSession::subscribe(&address, "BAD", "bob") {
+            Err(ClientError::SubscribePlayer(reason)) => assert_eq!(reason, "InvalidRegistrationToken"),
+            Err(e) => panic!("erreur inattendue: {}", e),
+            Ok(_) => panic!("l'inscription aurait dû échouer"),
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_subscribe_rejects_invalid_radar() {
+        let (address, server) = scripted_server(|s| {
+            s.recv().unwrap();
+            s.send(&Message::SubscribePlayerResult(SubscribePlayerResult::Ok)).unwrap();
+            s.send(&Message::RadarView(RadarView("pas un radar".into()))).unwrap();
+        });
+        match PlayerSession::subscribe(&address, "TOKEN", "carol") {
+            Err(ClientError::InvalidRadar(_)) => {}
+            Err(e) => panic!("erreur inattendue: {}", e),
+            Ok(_) => panic!("le radar aurait dû être refusé"),
+        }
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,245 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use shared::messages::{Action, ActionError, Hint, RelativeDirection};
+use shared::radar::{Passage, RadarGrid, RadarItem};
+
+use crate::maze_solver::{self, MazeSolver};
+use crate::session::{move_outcome, SessionEvent};
+use crate::world_map::WorldMap;
+
+/// Directions dans l'ordre de préférence de la main droite.
+const RIGHT_HAND: [RelativeDirection; 4] = [
+    RelativeDirection::Right,
+    RelativeDirection::Front,
+    RelativeDirection::Left,
+    RelativeDirection::Back,
+];
+
+/// Choix du prochain déplacement d'un joueur à partir de sa dernière vue radar.
+pub trait Strategy: Send {
+    fn name(&self) -> &'static str;
+
+    /// Appelée avec la vue radar initiale puis avec celle qui suit chaque réponse du serveur,
+    /// ou de nouveau avec la même vue après une attente. `None` : le joueur passe son tour.
+    fn next_move(&mut self, radar: &RadarGrid) -> Option<RelativeDirection>;
+
+    /// Appelée quand le serveur accepte un déplacement.
+    fn on_accepted(&mut self, _direction: &RelativeDirection) {}
+
+    /// Appelée quand le serveur refuse le déplacement proposé.
+    fn on_rejected(&mut self, _direction: &RelativeDirection, _error: &ActionError) {}
+
+    /// Appelée quand le joueur a passé son tour sans rien envoyer au serveur.
+    fn on_wait(&mut self) {}
+
+    /// Appelée pour chaque indice reçu par le joueur.
+    fn on_hint(&mut self, _hint: &Hint) {}
+}
+
+/// Tient la stratégie au courant de la réponse du serveur à `action` : l'issue du déplacement
+/// d'abord, puis les indices, que le serveur calcule depuis la nouvelle orientation du joueur.
+pub fn feed(strategy: &mut dyn Strategy, action: &Action, events: &[SessionEvent]) {
+    if let Action::MoveTo(direction) = action {
+        match move_outcome(events) {
+            Some(Ok(())) => strategy.on_accepted(direction),
+            Some(Err(error)) => strategy.on_rejected(direction, error),
+            None => {}
+        }
+    }
+    for event in events {
+        if let SessionEvent::Hint(hint) = event {
+            strategy.on_hint(hint);
+        }
+    }
+}
+
+/// Solveurs proposés pour jouer en ligne, chacun avec un comportement distinct. Les recherches
+/// de [`maze_solver::SOLVERS`] (`astar`, `bfs`, `dijkstra`) jouent toutes comme `frontier`.
+pub const ONLINE_SOLVERS: [&str; 5] = ["frontier", "tremaux", "right-hand", "left-hand", "pledge"];
+
+/// Stratégies proposées par le bot : la marche aléatoire puis les [`ONLINE_SOLVERS`].
+pub fn names() -> Vec<&'static str> {
+    let mut names = vec!["random"];
+    names.extend(ONLINE_SOLVERS);
+    names
+}
+
+/// Construit une stratégie à partir de son nom. `seed` initialise les stratégies aléatoires.
+/// `frontier` est l'exploration de [`maze_solver::FrontierPlanner`] ; les noms des recherches
+/// de [`maze_solver::SOLVERS`] restent acceptés et donnent la même exploration.
+pub fn by_name(name: &str, seed: u64) -> Option<Box<dyn Strategy>> {
+    match name {
+        "random" => Some(Box::new(RandomWalk::new(seed))),
+        "frontier" => by_name("astar", seed),
+        _ => maze_solver::solver_by_name(name).map(|solver| Box::new(SolverStrategy::new(solver)) as Box<dyn Strategy>),
+    }
+}
+
+/// Cellule voisine du joueur dans la direction `dir`, en coordonnées de la vue.
+fn neighbour_cell(dir: &RelativeDirection) -> (usize, usize) {
+    match dir {
+        RelativeDirection::Front => (0, 1),
+        RelativeDirection::Right => (1, 2),
+        RelativeDirection::Back => (2, 1),
+        RelativeDirection::Left => (1, 0),
+    }
+}
+
+/// Le joueur est sur la sortie.
+pub fn reached_target(radar: &RadarGrid) -> bool {
+    radar.item(1, 1) == RadarItem::Target
+}
+
+/// Direction d'une sortie voisine accessible, quelle que soit la stratégie.
+pub fn target_direction(radar: &RadarGrid) -> Option<RelativeDirection> {
+    RIGHT_HAND.into_iter().find(|dir| {
+        let (row, col) = neighbour_cell(dir);
+        radar.item(row, col) == RadarItem::Target && radar.player_passage(dir.clone()) == Passage::Open
+    })
+}
+
+/// Marche aléatoire qui ne fait demi-tour que dans une impasse.
+pub struct RandomWalk {
+    rng: StdRng,
+}
+
+impl RandomWalk {
+    pub fn new(seed: u64) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+impl Strategy for RandomWalk {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn next_move(&mut self, radar: &RadarGrid) -> Option<RelativeDirection> {
+        let forward: Vec<RelativeDirection> = RIGHT_HAND[..3].iter()
+            .filter(|dir| radar.player_passage((*dir).clone()) == Passage::Open)
+            .cloned()
+            .collect();
+        Some(forward.choose(&mut self.rng).cloned().unwrap_or(RelativeDirection::Back))
+    }
+}
+
+/// Joue un [`MazeSolver`] en ligne sur une carte reconstruite à partir des vues radar.
+/// Chaque réponse du serveur et chaque attente comptent pour un tour de la carte.
+pub struct SolverStrategy {
+    map: WorldMap,
+    solver: Box<dyn MazeSolver>,
+    /// La vue radar courante a déjà été intégrée à la carte.
+    observed: bool,
+}
+
+impl SolverStrategy {
+    pub fn new(solver: Box<dyn MazeSolver>) -> Self {
+        Self { map: WorldMap::default(), solver, observed: false }
+    }
+}
+
+impl Strategy for SolverStrategy {
+    fn name(&self) -> &'static str {
+        self.solver.name()
+    }
+
+    fn next_move(&mut self, radar: &RadarGrid) -> Option<RelativeDirection> {
+        if !self.observed {
+            self.map.observe(radar);
+            self.observed = true;
+        }
+        self.solver.next_move(&self.map)
+    }
+
+    fn on_accepted(&mut self, direction: &RelativeDirection) {
+        self.map.apply_move(direction);
+        self.map.tick();
+        self.observed = false;
+    }
+
+    fn on_rejected(&mut self, direction: &RelativeDirection, error: &ActionError) {
+        if *error == ActionError::CannotPassThroughOpponent {
+            self.map.mark_blocked(direction);
+        }
+        self.map.tick();
+        self.observed = false;
+    }
+
+    fn on_wait(&mut self) {
+        self.map.tick();
+    }
+
+    fn on_hint(&mut self, hint: &Hint) {
+        match hint {
+            Hint::RelativeCompass { angle } => self.solver.observe_compass(*angle, self.map.facing()),
+            Hint::GridSize { columns, rows } => self.map.set_grid_size(*columns, *rows),
+            Hint::Secret(_) | Hint::SOSHelper => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn radar(open: &[RelativeDirection]) -> RadarGrid {
+        let mut grid = RadarGrid::default();
+        for row in 0..4 {
+            for col in 0..3 {
+                grid.set_horizontal(row, col, Passage::Wall);
+                grid.set_vertical(col, row, Passage::Wall);
+            }
+        }
+        for dir in open {
+            match dir {
+                RelativeDirection::Front => grid.set_horizontal(1, 1, Passage::Open),
+                RelativeDirection::Back => grid.set_horizontal(2, 1, Passage::Open),
+                RelativeDirection::Left => grid.set_vertical(1, 1, Passage::Open),
+                RelativeDirection::Right => grid.set_vertical(1, 2, Passage::Open),
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn test_right_hand_preference() {
+        let next = |open: &[RelativeDirection]| by_name("right-hand", 0).unwrap().next_move(&radar(open));
+        assert_eq!(next(&[RelativeDirection::Front, RelativeDirection::Right]), Some(RelativeDirection::Right));
+        assert_eq!(next(&[RelativeDirection::Left, RelativeDirection::Front]), Some(RelativeDirection::Front));
+        assert_eq!(next(&[RelativeDirection::Back]), Some(RelativeDirection::Back));
+    }
+
+    #[test]
+    fn test_random_walk_avoids_walls() {
+        let mut strategy = by_name("random", 7).unwrap();
+        let grid = radar(&[RelativeDirection::Left, RelativeDirection::Back]);
+        for _ in 0..20 {
+            assert_eq!(strategy.next_move(&grid), Some(RelativeDirection::Left));
+        }
+        assert!(by_name("inconnue", 0).is_none());
+    }
+
+    #[test]
+    fn test_names_build_strategies() {
+        for name in names() {
+            assert!(by_name(name, 0).is_some(), "{}", name);
+        }
+        // Les recherches restent acceptées, sans être proposées.
+        for name in ["astar", "bfs", "dijkstra"] {
+            assert!(!names().contains(&name));
+            assert!(by_name(name, 0).is_some());
+        }
+    }
+
+    #[test]
+    fn test_target_detection() {
+        let mut grid = radar(&[RelativeDirection::Left]);
+        grid.set_item(1, 0, RadarItem::Target);
+        grid.set_item(0, 1, RadarItem::Target);
+        assert_eq!(target_direction(&grid), Some(RelativeDirection::Left));
+        assert!(!reached_target(&grid));
+        grid.set_item(1, 1, RadarItem::Target);
+        assert!(reached_target(&grid));
+    }
+}
@@ -1,4 +1,4 @@
-use eframe::{egui, Frame};
+use eframe::egui;
 use egui::TextureOptions;
 use std::path::Path;
 use image::io::Reader as ImageReader;
@@ -9,10 +9,35 @@ pub enum RegistrationMode {
     Join,
 }
 
+/// Demandes de l'interface d'inscription au thread réseau.
 #[derive(Debug)]
 pub enum RegistrationData {
+    /// Enregistre l'équipe puis inscrit chacun de ses membres.
     Create { team_name: String, team_members: Vec<String> },
-    Join { token: String, player_name: String },
+    /// Inscrit un joueur avec le token d'une équipe : pour rejoindre, réessayer ou ajouter un joueur.
+    Subscribe { token: String, player_name: String },
+    /// Ferme les sessions déjà ouvertes pour recommencer l'inscription.
+    Reset,
+    /// Tous les joueurs attendus sont inscrits : la partie peut commencer.
+    Start,
+}
+
+/// État de l'inscription d'un joueur auprès du serveur.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemberStatus {
+    /// Nom saisi, pas encore envoyé.
+    Draft,
+    Pending,
+    Subscribed,
+    /// Refusé, avec le message du serveur.
+    Rejected(String),
+}
+
+/// Joueur envoyé au serveur, ou à envoyer, avec l'état de son inscription.
+#[derive(Debug, Clone)]
+pub struct Subscription {
+    pub player_name: String,
+    pub status: MemberStatus,
 }
 
 /// Interface GUI pour la création ou la jonction d'une équipe.
@@ -25,6 +50,16 @@ pub struct TeamRegistrationApp {
     pub join_token: String,
     pub player_name: String,
     pub title_texture: Option<egui::TextureHandle>,
+    /// Token de l'équipe, une fois enregistrée ou saisi pour la rejoindre
+    pub token: Option<String>,
+    /// Nombre de joueurs attendus par le serveur, connu à l'enregistrement de l'équipe
+    pub expected_players: Option<u8>,
+    /// Inscription de chaque joueur, dans l'ordre d'envoi
+    pub subscriptions: Vec<Subscription>,
+    /// L'enregistrement de l'équipe attend la réponse du serveur
+    pub team_pending: bool,
+    /// Refus de l'enregistrement de l'équipe
+    pub team_error: Option<String>,
 }
 
 impl Default for TeamRegistrationApp {
@@ -36,11 +71,136 @@ impl Default for TeamRegistrationApp {
             join_token: String::new(),
             player_name: String::new(),
             title_texture: None,
+            token: None,
+            expected_players: None,
+            subscriptions: Vec::new(),
+            team_pending: false,
+            team_error: None,
         }
     }
 }
 
 impl TeamRegistrationApp {
+    /// L'équipe est enregistrée : les membres envoyés attendent leur inscription.
+    pub fn on_team_registered(&mut self, token: String, expected_players: u8) {
+        self.token = Some(token);
+        self.expected_players = Some(expected_players);
+        self.team_pending = false;
+        self.team_error = None;
+    }
+
+    /// L'enregistrement de l'équipe est refusé : le formulaire redevient modifiable.
+    pub fn on_team_failed(&mut self, reason: String) {
+        self.team_pending = false;
+        self.team_error = Some(reason);
+        self.subscriptions.clear();
+    }
+
+    /// Met à jour la première inscription en attente pour ce joueur.
+    pub fn on_player_result(&mut self, player_name: &str, status: MemberStatus) {
+        if let Some(subscription) = self.subscriptions.iter_mut()
+            .find(|s| s.player_name == player_name && s.status == MemberStatus::Pending)
+        {
+            subscription.status = status;
+        }
+    }
+
+    /// Joueurs inscrits auprès du serveur.
+    pub fn subscribed_players(&self) -> Vec<String> {
+        self.subscriptions.iter()
+            .filter(|s| s.status == MemberStatus::Subscribed)
+            .map(|s| s.player_name.clone())
+            .collect()
+    }
+
+    /// Vrai quand plus rien n'est en attente et que tous les joueurs attendus sont inscrits.
+    /// Sans nombre attendu (équipe rejointe), chaque joueur envoyé doit être inscrit.
+    pub fn is_complete(&self) -> bool {
+        let subscribed = self.subscribed_players().len();
+        let expected = self.expected_players.map(usize::from).unwrap_or(self.subscriptions.len());
+        subscribed > 0
+            && subscribed >= expected
+            && !self.subscriptions.iter().any(|s| s.status == MemberStatus::Pending)
+    }
+
+    /// Oublie l'équipe enregistrée pour revenir aux formulaires.
+    fn reset(&mut self) {
+        self.token = None;
+        self.expected_players = None;
+        self.subscriptions.clear();
+        self.team_pending = false;
+        self.team_error = None;
+    }
+
+    /// Suivi des inscriptions une fois le token connu : état de chaque joueur,
+    /// édition et nouvel essai des refusés, ajout d'un joueur et passage à la partie.
+    fn progress_ui<F: FnMut(RegistrationData)>(&mut self, ui: &mut egui::Ui, on_register: &mut F) {
+        let token = self.token.clone().unwrap_or_default();
+        ui.heading(match self.mode {
+            RegistrationMode::Create => format!("Équipe {}", self.team_name),
+            RegistrationMode::Join => "Équipe rejointe".to_string(),
+        });
+        ui.label(format!("Token : {}", token));
+        let subscribed = self.subscribed_players().len();
+        match self.expected_players {
+            Some(expected) => ui.label(format!("Joueurs inscrits : {} / {} attendus", subscribed, expected)),
+            None => ui.label(format!("Joueurs inscrits : {}", subscribed)),
+        };
+        ui.add_space(10.0);
+
+        let mut removed = None;
+        for (i, subscription) in self.subscriptions.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                match &subscription.status {
+                    MemberStatus::Pending => {
+                        ui.spinner();
+                        ui.label(&subscription.player_name);
+                    }
+                    MemberStatus::Subscribed => {
+                        ui.label(egui::RichText::new("✔").color(egui::Color32::GREEN));
+                        ui.label(&subscription.player_name);
+                    }
+                    MemberStatus::Draft | MemberStatus::Rejected(_) => {
+                        ui.add_sized([200.0, 24.0], egui::TextEdit::singleline(&mut subscription.player_name));
+                        let label = if subscription.status == MemberStatus::Draft { "Inscrire" } else { "Réessayer" };
+                        if ui.add_enabled(!subscription.player_name.trim().is_empty(), egui::Button::new(label)).clicked() {
+                            subscription.player_name = subscription.player_name.trim().to_string();
+                            subscription.status = MemberStatus::Pending;
+                            on_register(RegistrationData::Subscribe {
+                                token: token.clone(),
+                                player_name: subscription.player_name.clone(),
+                            });
+                        }
+                        if ui.button("Retirer").clicked() {
+                            removed = Some(i);
+                        }
+                    }
+                }
+            });
+            if let MemberStatus::Rejected(reason) = &subscription.status {
+                ui.label(egui::RichText::new(reason).color(egui::Color32::LIGHT_RED));
+            }
+        }
+        if let Some(i) = removed {
+            self.subscriptions.remove(i);
+        }
+
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            if ui.button("Ajouter un joueur").clicked() {
+                self.subscriptions.push(Subscription { player_name: String::new(), status: MemberStatus::Draft });
+            }
+            if ui.button("Recommencer").clicked() {
+                on_register(RegistrationData::Reset);
+                self.reset();
+            }
+        });
+        ui.add_space(10.0);
+        if ui.add_enabled(self.is_complete(), egui::Button::new("Continuer")).clicked() {
+            on_register(RegistrationData::Start);
+        }
+    }
+
     /// Affiche l'interface de création/rejoindre et appelle le callback avec RegistrationData.
     pub fn ui<F: FnMut(RegistrationData)>(&mut self, ui: &mut egui::Ui, mut on_register: F) {
         // Charger l'image si non déjà chargée.
@@ -50,19 +210,23 @@ impl TeamRegistrationApp {
             }
         }
         
-        // Toggle entre les modes "Créer" et "Rejoindre"
-        ui.horizontal(|ui| {
+        // Toggle entre les modes "Créer" et "Rejoindre", tant qu'aucune équipe n'est choisie.
+        ui.add_enabled_ui(self.token.is_none() && !self.team_pending, |ui| ui.horizontal(|ui| {
             if ui.selectable_label(self.mode == RegistrationMode::Create, "Créer une équipe").clicked() {
                 self.mode = RegistrationMode::Create;
             }
             if ui.selectable_label(self.mode == RegistrationMode::Join, "Rejoindre une équipe").clicked() {
                 self.mode = RegistrationMode::Join;
             }
-        });
+        }));
         
         ui.columns(2, |cols| {
-            // Colonne gauche : Formulaire.
+            // Colonne gauche : Formulaire, puis suivi des inscriptions.
             cols[0].vertical(|ui| {
+                if self.token.is_some() {
+                    self.progress_ui(ui, &mut on_register);
+                    return;
+                }
                 match self.mode {
                     RegistrationMode::Create => {
                         ui.heading("Création d'équipe");
@@ -107,11 +271,28 @@ impl TeamRegistrationApp {
                             ui.add_space(5.0);
                         }
                         ui.add_space(10.0);
-                        if ui.button("Enregistrer l'équipe").clicked() {
-                            on_register(RegistrationData::Create {
-                                team_name: self.team_name.clone(),
-                                team_members: self.team_members.clone(),
-                            });
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(!self.team_pending, egui::Button::new("Enregistrer l'équipe")).clicked() {
+                                let team_members: Vec<String> = self.team_members.iter()
+                                    .map(|m| m.trim().to_string())
+                                    .filter(|m| !m.is_empty())
+                                    .collect();
+                                self.subscriptions = team_members.iter()
+                                    .map(|m| Subscription { player_name: m.clone(), status: MemberStatus::Pending })
+                                    .collect();
+                                self.team_pending = true;
+                                self.team_error = None;
+                                on_register(RegistrationData::Create {
+                                    team_name: self.team_name.trim().to_string(),
+                                    team_members,
+                                });
+                            }
+                            if self.team_pending {
+                                ui.spinner();
+                            }
+                        });
+                        if let Some(error) = &self.team_error {
+                            ui.label(egui::RichText::new(error).color(egui::Color32::LIGHT_RED));
                         }
                     },
                     RegistrationMode::Join => {
@@ -152,10 +333,11 @@ impl TeamRegistrationApp {
                             });
                         ui.add_space(10.0);
                         if ui.button("Rejoindre l'équipe").clicked() {
-                            on_register(RegistrationData::Join {
-                                token: self.join_token.clone(),
-                                player_name: self.player_name.clone(),
-                            });
+                            let token = self.join_token.trim().to_string();
+                            let player_name = self.player_name.trim().to_string();
+                            self.token = Some(token.clone());
+                            self.subscriptions = vec![Subscription { player_name: player_name.clone(), status: MemberStatus::Pending }];
+                            on_register(RegistrationData::Subscribe { token, player_name });
                         }
                     }
                 }
@@ -206,3 +388,42 @@ fn load_title_image(ctx: &egui::Context, path: &str) -> Option<egui::TextureHand
     let color_image = egui::ColorImage::from_rgba_unmultiplied(size, &pixels);
     Some(ctx.load_texture("team_title", color_image, TextureOptions::default()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(names: &[&str]) -> Vec<Subscription> {
+        names.iter()
+            .map(|n| Subscription { player_name: n.to_string(), status: MemberStatus::Pending })
+            .collect()
+    }
+
+    #[test]
+    fn test_complete_once_expected_players_are_subscribed() {
+        let mut app = TeamRegistrationApp { subscriptions: pending(&["a", "b", "c"]), ..TeamRegistrationApp::default() };
+        app.on_team_registered("TOKEN".into(), 2);
+        app.on_player_result("a", MemberStatus::Subscribed);
+        app.on_player_result("b", MemberStatus::Rejected("AlreadyRegistered".into()));
+        assert!(!app.is_complete());
+
+        // Le refusé est renommé puis renvoyé : tant qu'il est en attente, rien n'est prêt.
+        app.subscriptions[1] = Subscription { player_name: "b2".into(), status: MemberStatus::Pending };
+        app.on_player_result("b2", MemberStatus::Subscribed);
+        assert!(!app.is_complete());
+        app.on_player_result("c", MemberStatus::Rejected("TooManyPlayers".into()));
+        assert!(app.is_complete());
+        assert_eq!(app.subscribed_players(), vec!["a".to_string(), "b2".to_string()]);
+    }
+
+    #[test]
+    fn test_rejections_block_completion() {
+        let mut app = TeamRegistrationApp { token: Some("TOKEN".into()), subscriptions: pending(&["a"]), ..TeamRegistrationApp::default() };
+        app.on_player_result("a", MemberStatus::Rejected("InvalidRegistrationToken".into()));
+        assert!(!app.is_complete());
+
+        app.on_team_failed("AlreadyRegistered".into());
+        assert!(app.subscriptions.is_empty());
+        assert!(!app.is_complete());
+    }
+}
@@ -0,0 +1,657 @@
+use std::collections::{HashMap, HashSet};
+
+use shared::messages::RelativeDirection;
+use shared::radar::{Passage, RadarGrid, RadarItem};
+
+/// Nombre de tours pendant lesquels un adversaire ou un monstre est supposé rester là où il a été vu.
+pub const THREAT_DECAY: u32 = 6;
+
+/// Position dans le repère de la carte : (ligne, colonne), la ligne croît vers le « sud ».
+/// L'origine est la cellule de départ du joueur et le « nord » son orientation initiale :
+/// le repère de la carte est donc tourné par rapport à celui du serveur, qui reste inconnu.
+pub type MapPos = (i64, i64);
+
+/// Orientation absolue dans le repère de la carte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn turn_left(self) -> Direction {
+        self.turn_right().turn_right().turn_right()
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.turn_right().turn_right()
+    }
+
+    /// Orientation obtenue en partant dans la direction relative `dir`.
+    pub fn turn(self, dir: &RelativeDirection) -> Direction {
+        match dir {
+            RelativeDirection::Front => self,
+            RelativeDirection::Right => self.turn_right(),
+            RelativeDirection::Back => self.opposite(),
+            RelativeDirection::Left => self.turn_left(),
+        }
+    }
+
+    /// Direction relative à prendre, en regardant vers `self`, pour partir vers `target`.
+    pub fn relative_to(self, target: Direction) -> RelativeDirection {
+        if target == self {
+            RelativeDirection::Front
+        } else if target == self.turn_right() {
+            RelativeDirection::Right
+        } else if target == self.turn_left() {
+            RelativeDirection::Left
+        } else {
+            RelativeDirection::Back
+        }
+    }
+
+    pub fn as_offset(self) -> (i64, i64) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::East => (0, 1),
+            Direction::South => (1, 0),
+            Direction::West => (0, -1),
+        }
+    }
+
+    pub fn step(self, (r, c): MapPos) -> MapPos {
+        let (dr, dc) = self.as_offset();
+        (r + dr, c + dc)
+    }
+}
+
+/// Ce que l'on sait d'une cellule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellState {
+    Unknown,
+    Inside,
+    /// Hors du labyrinthe : la vue radar n'en définit pas tous les passages.
+    Outside,
+}
+
+/// Occupant d'une cellule qui bloque le passage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Threat {
+    Enemy,
+    Monster,
+}
+
+/// Bords du labyrinthe (dernière ligne ou colonne intérieure), quand ils sont connus.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bounds {
+    pub top: Option<i64>,
+    pub bottom: Option<i64>,
+    pub left: Option<i64>,
+    pub right: Option<i64>,
+}
+
+impl Bounds {
+    pub fn contains(&self, (r, c): MapPos) -> bool {
+        self.top.is_none_or(|top| r >= top)
+            && self.bottom.is_none_or(|bottom| r <= bottom)
+            && self.left.is_none_or(|left| c >= left)
+            && self.right.is_none_or(|right| c <= right)
+    }
+}
+
+/// Changement de repère d'une carte vers une autre : rotation de `quarter_turns` quarts de tour
+/// dans le sens horaire autour de l'origine, puis translation de `offset`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Frame {
+    pub quarter_turns: u8,
+    pub offset: MapPos,
+}
+
+impl Frame {
+    pub const IDENTITY: Frame = Frame { quarter_turns: 0, offset: (0, 0) };
+
+    pub fn position(&self, (mut r, mut c): MapPos) -> MapPos {
+        for _ in 0..self.quarter_turns % 4 {
+            (r, c) = (c, -r);
+        }
+        (r + self.offset.0, c + self.offset.1)
+    }
+
+    pub fn direction(&self, mut dir: Direction) -> Direction {
+        for _ in 0..self.quarter_turns % 4 {
+            dir = dir.turn_right();
+        }
+        dir
+    }
+}
+
+/// Carte globale reconstruite à partir des vues radar successives d'un joueur.
+/// La position et l'orientation du joueur sont déduites des déplacements acceptés par le serveur.
+#[derive(Debug, Clone)]
+pub struct WorldMap {
+    position: MapPos,
+    facing: Direction,
+    /// Passage au nord de la cellule (r, c).
+    horizontal: HashMap<MapPos, Passage>,
+    /// Passage à l'ouest de la cellule (r, c).
+    vertical: HashMap<MapPos, Passage>,
+    cells: HashMap<MapPos, CellState>,
+    /// Dernier élément vu sur chaque cellule.
+    items: HashMap<MapPos, RadarItem>,
+    /// Tour de la dernière observation de chaque cellule, pour l'oubli des menaces.
+    seen: HashMap<MapPos, u32>,
+    /// Tours écoulés, avancés par [`WorldMap::tick`].
+    turn: u32,
+    visited: HashSet<MapPos>,
+    target: Option<MapPos>,
+    /// Taille annoncée par `Hint::GridSize` : (colonnes, lignes) du repère du serveur.
+    grid_size: Option<(u32, u32)>,
+    bounds: Bounds,
+}
+
+impl Default for WorldMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorldMap {
+    /// Carte vide, joueur à l'origine regardant vers le nord.
+    pub fn new() -> Self {
+        let mut visited = HashSet::new();
+        visited.insert((0, 0));
+        Self {
+            position: (0, 0),
+            facing: Direction::North,
+            horizontal: HashMap::new(),
+            vertical: HashMap::new(),
+            cells: HashMap::new(),
+            items: HashMap::new(),
+            seen: HashMap::new(),
+            turn: 0,
+            visited,
+            target: None,
+            grid_size: None,
+            bounds: Bounds::default(),
+        }
+    }
+
+    pub fn position(&self) -> MapPos {
+        self.position
+    }
+
+    pub fn facing(&self) -> Direction {
+        self.facing
+    }
+
+    /// Cellule de la sortie, si elle a été vue.
+    pub fn target(&self) -> Option<MapPos> {
+        self.target
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    pub fn item(&self, pos: MapPos) -> RadarItem {
+        self.items.get(&pos).copied().unwrap_or(RadarItem::None)
+    }
+
+    /// Adversaire ou monstre vu sur la cellule il y a moins de [`THREAT_DECAY`] tours.
+    pub fn threat(&self, pos: MapPos) -> Option<Threat> {
+        let seen = *self.seen.get(&pos)?;
+        if self.turn - seen >= THREAT_DECAY {
+            return None;
+        }
+        match self.item(pos) {
+            RadarItem::Enemy => Some(Threat::Enemy),
+            RadarItem::Monster => Some(Threat::Monster),
+            _ => None,
+        }
+    }
+
+    /// Menaces encore d'actualité.
+    pub fn threats(&self) -> impl Iterator<Item = (MapPos, Threat)> + '_ {
+        self.seen.keys().filter_map(|pos| self.threat(*pos).map(|threat| (*pos, threat)))
+    }
+
+    /// Fait passer un tour : réponse du serveur ou attente du joueur. Les menaces vues s'estompent.
+    pub fn tick(&mut self) {
+        self.turn += 1;
+    }
+
+    /// Le serveur a refusé le déplacement `dir` avec `CannotPassThroughOpponent` :
+    /// la cellule visée est occupée.
+    pub fn mark_blocked(&mut self, dir: &RelativeDirection) {
+        let pos = self.facing.turn(dir).step(self.position);
+        if !matches!(self.item(pos), RadarItem::Enemy | RadarItem::Monster) {
+            self.items.insert(pos, RadarItem::Enemy);
+        }
+        self.seen.insert(pos, self.turn);
+    }
+
+    pub fn is_visited(&self, pos: MapPos) -> bool {
+        self.visited.contains(&pos)
+    }
+
+    pub fn cell(&self, pos: MapPos) -> CellState {
+        if !self.bounds.contains(pos) {
+            return CellState::Outside;
+        }
+        self.cells.get(&pos).copied().unwrap_or(CellState::Unknown)
+    }
+
+    /// La cellule peut appartenir au labyrinthe.
+    pub fn contains(&self, pos: MapPos) -> bool {
+        self.cell(pos) != CellState::Outside
+    }
+
+    /// Cellules connues comme intérieures au labyrinthe.
+    pub fn known_cells(&self) -> impl Iterator<Item = MapPos> + '_ {
+        self.cells.iter()
+            .filter(|(pos, state)| **state == CellState::Inside && self.bounds.contains(**pos))
+            .map(|(pos, _)| *pos)
+    }
+
+    /// Lignes et colonnes extrêmes des cellules intérieures connues.
+    pub fn extent(&self) -> Option<(MapPos, MapPos)> {
+        self.known_cells().fold(None, |acc, (r, c)| match acc {
+            None => Some(((r, c), (r, c))),
+            Some(((r0, c0), (r1, c1))) => Some(((r0.min(r), c0.min(c)), (r1.max(r), c1.max(c)))),
+        })
+    }
+
+    fn edge_key(pos: MapPos, dir: Direction) -> (bool, MapPos) {
+        let (r, c) = pos;
+        match dir {
+            Direction::North => (true, (r, c)),
+            Direction::South => (true, (r + 1, c)),
+            Direction::West => (false, (r, c)),
+            Direction::East => (false, (r, c + 1)),
+        }
+    }
+
+    /// État du passage du côté `dir` de la cellule. Les bords connus du labyrinthe sont des murs.
+    pub fn edge(&self, pos: MapPos, dir: Direction) -> Passage {
+        if self.contains(pos) && !self.bounds.contains(dir.step(pos)) {
+            return Passage::Wall;
+        }
+        let (horizontal, key) = Self::edge_key(pos, dir);
+        let edges = if horizontal { &self.horizontal } else { &self.vertical };
+        edges.get(&key).copied().unwrap_or(Passage::Undefined)
+    }
+
+    fn set_edge(&mut self, pos: MapPos, dir: Direction, passage: Passage) {
+        if passage == Passage::Undefined {
+            return;
+        }
+        let (horizontal, key) = Self::edge_key(pos, dir);
+        let edges = if horizontal { &mut self.horizontal } else { &mut self.vertical };
+        edges.insert(key, passage);
+    }
+
+    /// Cellule absolue correspondant à la case (`row`, `col`) de la vue radar courante.
+    fn view_cell(&self, row: usize, col: usize) -> MapPos {
+        let front = 1 - row as i64;
+        let right = col as i64 - 1;
+        let (fr, fc) = self.facing.as_offset();
+        let (rr, rc) = self.facing.turn_right().as_offset();
+        (self.position.0 + front * fr + right * rr, self.position.1 + front * fc + right * rc)
+    }
+
+    /// Intègre une vue radar prise à la position et dans l'orientation courantes.
+    pub fn observe(&mut self, radar: &RadarGrid) {
+        let front = self.facing;
+        for row in 0..3 {
+            for col in 0..3 {
+                let pos = self.view_cell(row, col);
+                let sides = [
+                    (front, radar.passage(row, col, RelativeDirection::Front)),
+                    (front.opposite(), radar.passage(row, col, RelativeDirection::Back)),
+                    (front.turn_left(), radar.passage(row, col, RelativeDirection::Left)),
+                    (front.turn_right(), radar.passage(row, col, RelativeDirection::Right)),
+                ];
+                // Une cellule intérieure a toujours ses quatre passages définis ; une cellule
+                // extérieure a au moins une voisine extérieure, donc un passage indéfini.
+                let inside = sides.iter().all(|(_, passage)| *passage != Passage::Undefined);
+                self.cells.insert(pos, if inside { CellState::Inside } else { CellState::Outside });
+                for (dir, passage) in sides {
+                    self.set_edge(pos, dir, passage);
+                }
+                if inside {
+                    let item = radar.item(row, col);
+                    self.items.insert(pos, item);
+                    self.seen.insert(pos, self.turn);
+                    if item == RadarItem::Target {
+                        self.target = Some(pos);
+                    }
+                }
+            }
+        }
+        self.update_bounds();
+    }
+
+    /// Déplace le joueur après un déplacement accepté par le serveur.
+    pub fn apply_move(&mut self, dir: &RelativeDirection) {
+        let heading = self.facing.turn(dir);
+        self.position = heading.step(self.position);
+        self.facing = heading;
+        self.visited.insert(self.position);
+    }
+
+    /// Enregistre la taille annoncée par `Hint::GridSize` et en déduit les bords manquants.
+    pub fn set_grid_size(&mut self, columns: u32, rows: u32) {
+        self.grid_size = Some((columns, rows));
+        self.update_bounds();
+    }
+
+    /// Déplace le joueur suivi par la carte, quand elle est partagée entre plusieurs joueurs.
+    pub fn place(&mut self, position: MapPos, facing: Direction) {
+        self.position = position;
+        self.facing = facing;
+        self.visited.insert(position);
+    }
+
+    /// Passages enregistrés, chacun désigné par une cellule et un côté.
+    fn stored_edges(&self) -> impl Iterator<Item = (MapPos, Direction, Passage)> + '_ {
+        let horizontal = self.horizontal.iter().map(|(pos, p)| (*pos, Direction::North, *p));
+        let vertical = self.vertical.iter().map(|(pos, p)| (*pos, Direction::West, *p));
+        horizontal.chain(vertical)
+    }
+
+    /// Nombre de passages connus des deux cartes une fois `other` ramenée dans ce repère par `frame`,
+    /// ou `None` si les deux cartes se contredisent (passage, cellule ou sortie).
+    pub fn agreement(&self, other: &WorldMap, frame: Frame) -> Option<usize> {
+        let mut agreed = 0;
+        for (pos, dir, passage) in other.stored_edges() {
+            match self.edge(frame.position(pos), frame.direction(dir)) {
+                Passage::Undefined => {}
+                known if known == passage => agreed += 1,
+                _ => return None,
+            }
+        }
+        for (pos, state) in &other.cells {
+            let here = self.cell(frame.position(*pos));
+            if here != CellState::Unknown && *state != here {
+                return None;
+            }
+        }
+        if let (Some(target), Some(other_target)) = (self.target, other.target) {
+            if target != frame.position(other_target) {
+                return None;
+            }
+        }
+        Some(agreed)
+    }
+
+    /// Cherche le changement de repère qui superpose `other` à cette carte sans contradiction,
+    /// avec au moins `min_agreement` passages en commun et au moins deux fois plus que toute autre
+    /// superposition sans contradiction. `None` tant que le recouvrement est ambigu.
+    pub fn align(&self, other: &WorldMap, min_agreement: usize) -> Option<Frame> {
+        let cells: Vec<MapPos> = self.known_cells().collect();
+        let mut candidates = HashSet::new();
+        for quarter_turns in 0..4 {
+            let rotation = Frame { quarter_turns, offset: (0, 0) };
+            for pos in other.known_cells() {
+                let rotated = rotation.position(pos);
+                for cell in &cells {
+                    candidates.insert(Frame { quarter_turns, offset: (cell.0 - rotated.0, cell.1 - rotated.1) });
+                }
+            }
+        }
+        let mut best: Option<(Frame, usize)> = None;
+        let mut runner_up = 0;
+        for frame in candidates {
+            let Some(agreed) = self.agreement(other, frame) else {
+                continue;
+            };
+            match best {
+                Some((_, top)) if agreed <= top => runner_up = runner_up.max(agreed),
+                _ => {
+                    runner_up = best.map_or(0, |(_, top)| top);
+                    best = Some((frame, agreed));
+                }
+            }
+        }
+        best.filter(|(_, agreed)| *agreed >= min_agreement && *agreed >= 2 * runner_up)
+            .map(|(frame, _)| frame)
+    }
+
+    /// Ajoute à cette carte ce que `other` sait, ramené dans ce repère par `frame`.
+    /// La position suivie par cette carte ne change pas ; les menaces de `other`, datées
+    /// dans son propre décompte des tours, ne sont pas reprises.
+    pub fn merge(&mut self, other: &WorldMap, frame: Frame) {
+        for (pos, dir, passage) in other.stored_edges() {
+            self.set_edge(frame.position(pos), frame.direction(dir), passage);
+        }
+        for (pos, state) in &other.cells {
+            let pos = frame.position(*pos);
+            if *state == CellState::Inside || !self.cells.contains_key(&pos) {
+                self.cells.insert(pos, *state);
+            }
+        }
+        for (pos, item) in other.items.iter().filter(|(_, item)| !matches!(item, RadarItem::Enemy | RadarItem::Monster)) {
+            self.items.insert(frame.position(*pos), *item);
+        }
+        self.visited.extend(other.visited.iter().map(|pos| frame.position(*pos)));
+        if self.target.is_none() {
+            self.target = other.target.map(|target| frame.position(target));
+        }
+        if self.grid_size.is_none() {
+            self.grid_size = other.grid_size;
+        }
+        self.update_bounds();
+    }
+
+    /// Un bord passe entre une cellule intérieure et sa voisine extérieure.
+    fn update_bounds(&mut self) {
+        let pairs: Vec<(MapPos, Direction)> = self.cells.iter()
+            .filter(|(_, state)| **state == CellState::Inside)
+            .flat_map(|(pos, _)| Direction::ALL.into_iter().map(move |dir| (*pos, dir)))
+            .filter(|(pos, dir)| self.cells.get(&dir.step(*pos)) == Some(&CellState::Outside))
+            .collect();
+        for ((r, c), dir) in pairs {
+            match dir {
+                Direction::North => self.bounds.top = Some(r),
+                Direction::South => self.bounds.bottom = Some(r),
+                Direction::West => self.bounds.left = Some(c),
+                Direction::East => self.bounds.right = Some(c),
+            }
+        }
+        if let Some((row_size, column_size)) = self.axis_sizes() {
+            let bounds = &mut self.bounds;
+            fill_bound(&mut bounds.top, &mut bounds.bottom, row_size);
+            fill_bound(&mut bounds.left, &mut bounds.right, column_size);
+        }
+    }
+
+    /// Nombre de lignes et de colonnes dans le repère de la carte, quand l'orientation
+    /// relative au repère du serveur peut être déterminée.
+    fn axis_sizes(&self) -> Option<(i64, i64)> {
+        let (columns, rows) = self.grid_size?;
+        let (a, b) = (rows as i64, columns as i64);
+        if a == b {
+            return Some((a, a));
+        }
+        let (small, large) = (a.min(b), a.max(b));
+        let span = |low: Option<i64>, high: Option<i64>| low.zip(high).map(|(l, h)| h - l + 1);
+        let (min, max) = self.extent()?;
+        let row_size = if let Some(rows) = span(self.bounds.top, self.bounds.bottom) {
+            rows
+        } else if let Some(columns) = span(self.bounds.left, self.bounds.right) {
+            if columns == small { large } else { small }
+        } else if max.0 - min.0 + 1 > small {
+            large
+        } else if max.1 - min.1 + 1 > small {
+            small
+        } else {
+            return None;
+        };
+        Some((row_size, if row_size == small { large } else { small }))
+    }
+}
+
+fn fill_bound(low: &mut Option<i64>, high: &mut Option<i64>, size: i64) {
+    match (*low, *high) {
+        (Some(l), None) => *high = Some(l + size - 1),
+        (None, Some(h)) => *low = Some(h - size + 1),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use server::maze::{self, Maze};
+    use server::radar::{compute_radar, Entities};
+
+    fn layout() -> Maze {
+        Maze::from_ascii(
+            "
+            +-+-+-+-+
+            |   |   |
+            + +-+ + +
+            |     |E|
+            +-+ +-+ +
+            |       |
+            +-+-+-+-+
+            ",
+        )
+    }
+
+    /// Joueur simulé : position réelle côté serveur et carte côté client.
+    struct Walker {
+        maze: Maze,
+        start: maze::Position,
+        initial: maze::Direction,
+        position: maze::Position,
+        facing: maze::Direction,
+        map: WorldMap,
+    }
+
+    impl Walker {
+        fn new(maze: Maze, start: maze::Position, facing: maze::Direction) -> Self {
+            let mut walker = Walker { maze, start, initial: facing, position: start, facing, map: WorldMap::new() };
+            walker.look();
+            walker
+        }
+
+        fn look(&mut self) {
+            let entities = Entities { exit: Some(self.maze.exit()), ..Entities::default() };
+            self.map.observe(&compute_radar(&self.maze, self.position, self.facing, &entities));
+        }
+
+        fn walk(&mut self, dir: RelativeDirection) {
+            let heading = self.facing.turn(&dir);
+            self.position = self.maze.step(self.position, heading).expect("mur");
+            self.facing = heading;
+            self.map.apply_move(&dir);
+            self.look();
+        }
+
+        /// Cellule du serveur correspondant à une cellule de la carte.
+        fn absolute(&self, (r, c): MapPos) -> (i64, i64) {
+            let (fr, fc) = self.initial.as_offset();
+            let (rr, rc) = self.initial.turn_right().as_offset();
+            (self.start.0 as i64 - r * fr + c * rr, self.start.1 as i64 - r * fc + c * rc)
+        }
+
+        fn absolute_direction(&self, dir: Direction) -> maze::Direction {
+            let mut abs = self.initial;
+            let mut d = Direction::North;
+            while d != dir {
+                d = d.turn_right();
+                abs = abs.turn_right();
+            }
+            abs
+        }
+    }
+
+    #[test]
+    fn test_map_matches_maze_after_walk() {
+        let mut walker = Walker::new(layout(), (1, 1), maze::Direction::East);
+        for dir in [RelativeDirection::Front, RelativeDirection::Left, RelativeDirection::Right, RelativeDirection::Right] {
+            walker.walk(dir);
+        }
+        assert_eq!(walker.position, (1, 3));
+        assert_eq!(walker.absolute(walker.map.position()), (1, 3));
+
+        for pos in walker.map.known_cells().collect::<Vec<_>>() {
+            let (r, c) = walker.absolute(pos);
+            let abs = (r as usize, c as usize);
+            assert!(walker.maze.contains(abs), "{:?} devrait être hors du labyrinthe", abs);
+            for dir in Direction::ALL {
+                let expected = if walker.maze.is_wall(abs, walker.absolute_direction(dir)) { Passage::Wall } else { Passage::Open };
+                assert_eq!(walker.map.edge(pos, dir), expected, "{:?} {:?}", abs, dir);
+            }
+        }
+        assert_eq!(walker.map.target().map(|t| walker.absolute(t)), Some((1, 3)));
+    }
+
+    #[test]
+    fn test_map_grows_in_every_direction() {
+        let mut walker = Walker::new(Maze::open(5, 5), (2, 2), maze::Direction::South);
+        assert_eq!(walker.map.extent(), Some(((-1, -1), (1, 1))));
+        walker.walk(RelativeDirection::Back);
+        walker.walk(RelativeDirection::Right);
+        // Le nord de la carte est le sud du serveur : la carte s'étend vers le bas et la gauche.
+        assert_eq!(walker.map.position(), (1, -1));
+        assert_eq!(walker.map.extent(), Some(((-1, -2), (2, 1))));
+        assert_eq!(walker.map.bounds(), Bounds::default());
+        assert_eq!(walker.map.cell((-5, 0)), CellState::Unknown);
+    }
+
+    #[test]
+    fn test_bounds_from_border_and_grid_size() {
+        // 3 lignes x 4 colonnes, départ en (1, 1) face au nord : le repère de la carte est celui du serveur.
+        let mut walker = Walker::new(layout(), (1, 1), maze::Direction::North);
+        walker.walk(RelativeDirection::Left);
+        assert_eq!(walker.map.bounds().left, Some(-1));
+        assert!(walker.map.bounds().top.is_none());
+        assert_eq!(walker.map.edge((0, -1), Direction::West), Passage::Wall);
+
+        walker.map.set_grid_size(4, 3);
+        // Trois colonnes connues ne suffisent pas à dire si la largeur vaut 3 ou 4.
+        assert_eq!(walker.map.bounds().right, None);
+        walker.walk(RelativeDirection::Back);
+        walker.walk(RelativeDirection::Front);
+        let bounds = walker.map.bounds();
+        assert_eq!((bounds.top, bounds.left, bounds.right), (None, Some(-1), Some(2)));
+        assert_eq!(walker.map.cell((0, 3)), CellState::Outside);
+        assert_eq!(walker.map.edge((0, 2), Direction::East), Passage::Wall);
+    }
+
+    #[test]
+    fn test_threats_fade() {
+        let maze = Maze::open(3, 3);
+        let monster = Entities { monsters: vec![(0, 1)], ..Entities::default() };
+        let mut map = WorldMap::new();
+        map.observe(&compute_radar(&maze, (1, 1), maze::Direction::North, &monster));
+        assert_eq!(map.threat((-1, 0)), Some(Threat::Monster));
+        for _ in 1..THREAT_DECAY {
+            map.tick();
+        }
+        assert_eq!(map.threats().collect::<Vec<_>>(), vec![((-1, 0), Threat::Monster)]);
+        map.tick();
+        assert_eq!(map.threat((-1, 0)), None);
+
+        // Un refus du serveur signale un occupant, qu'une nouvelle vue peut démentir.
+        map.mark_blocked(&RelativeDirection::Right);
+        assert_eq!(map.threat((0, 1)), Some(Threat::Enemy));
+        map.observe(&compute_radar(&maze, (1, 1), maze::Direction::North, &Entities::default()));
+        assert_eq!(map.threat((0, 1)), None);
+    }
+}
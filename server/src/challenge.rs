@@ -0,0 +1,57 @@
+use shared::messages::Challenge;
+
+/// Règles de déclenchement des challenges, comptées en déplacements réussis du joueur.
+#[derive(Debug, Clone, Default)]
+pub struct ChallengeRules {
+    /// Un `SecretSumModulo` est lancé tous les N déplacements (jamais si `None`).
+    pub secret_sum_every: Option<u32>,
+    /// Un `SOS` est lancé tous les N déplacements, si le joueur a au moins un coéquipier connecté.
+    pub sos_every: Option<u32>,
+}
+
+/// Challenge en cours pour un joueur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunningChallenge {
+    SecretSumModulo(u64),
+    /// Le joueur est bloqué jusqu'à ce qu'un coéquipier le rejoigne sur sa cellule.
+    Sos,
+}
+
+impl RunningChallenge {
+    pub fn to_message(self) -> Challenge {
+        match self {
+            RunningChallenge::SecretSumModulo(modulo) => Challenge::SecretSumModulo(modulo),
+            RunningChallenge::Sos => Challenge::SOS,
+        }
+    }
+}
+
+impl ChallengeRules {
+    /// Challenge à lancer après le `moves`-ième déplacement d'un joueur. Un `SOS` demande
+    /// qu'un coéquipier puisse venir aider (`has_helper`).
+    pub fn trigger(&self, moves: u32, modulo: u64, has_helper: bool) -> Option<RunningChallenge> {
+        let fires = |every: Option<u32>| every.is_some_and(|n| n > 0 && moves.is_multiple_of(n));
+        if fires(self.secret_sum_every) {
+            Some(RunningChallenge::SecretSumModulo(modulo))
+        } else if has_helper && fires(self.sos_every) {
+            Some(RunningChallenge::Sos)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger() {
+        let rules = ChallengeRules { secret_sum_every: Some(3), sos_every: Some(2) };
+        assert_eq!(rules.trigger(1, 9, true), None);
+        assert_eq!(rules.trigger(2, 9, true), Some(RunningChallenge::Sos));
+        assert_eq!(rules.trigger(2, 9, false), None);
+        assert_eq!(rules.trigger(6, 9, true), Some(RunningChallenge::SecretSumModulo(9)));
+        assert_eq!(ChallengeRules::default().trigger(6, 9, true), None);
+    }
+}
@@ -0,0 +1,532 @@
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use shared::messages::{secret_sum_modulo, ActionError, Hint, Message, RadarView, RelativeDirection};
+
+use crate::challenge::{ChallengeRules, RunningChallenge};
+use crate::hints::{self as hint_rules, HintRules, SecretDelivery};
+use crate::maze::{Direction, Maze, Position};
+use crate::radar::{self, Entities};
+
+/// Identifiant d'un joueur inscrit.
+pub type PlayerId = usize;
+
+/// Paramètres d'une partie.
+#[derive(Debug, Clone)]
+pub struct GameConfig {
+    /// Nombre de joueurs attendus par équipe.
+    pub players_per_team: u8,
+    /// Graine du placement des joueurs, des secrets et des challenges.
+    pub seed: u64,
+    /// Déclenchement des challenges.
+    pub challenges: ChallengeRules,
+    /// Envoi des indices.
+    pub hints: HintRules,
+    /// Nombre de monstres errants. Ils bloquent les joueurs comme des adversaires
+    /// et font un pas au hasard après chaque tentative de déplacement d'un joueur.
+    pub monsters: usize,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            players_per_team: 3,
+            seed: 0,
+            challenges: ChallengeRules::default(),
+            hints: HintRules::default(),
+            monsters: 0,
+        }
+    }
+}
+
+/// Raisons de refus d'une inscription d'équipe ou de joueur (noms du protocole).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistrationError {
+    AlreadyRegistered,
+    InvalidName,
+    InvalidRegistrationToken,
+    TooManyPlayers,
+}
+
+impl RegistrationError {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RegistrationError::AlreadyRegistered => "AlreadyRegistered",
+            RegistrationError::InvalidName => "InvalidName",
+            RegistrationError::InvalidRegistrationToken => "InvalidRegistrationToken",
+            RegistrationError::TooManyPlayers => "TooManyPlayers",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Team {
+    pub name: String,
+    pub expected_players: u8,
+    pub players: Vec<PlayerId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub name: String,
+    pub team: String,
+    pub position: Position,
+    pub facing: Direction,
+    /// Le joueur a atteint la sortie.
+    pub escaped: bool,
+    /// Secret courant, renouvelé selon `HintRules::secrets`.
+    pub secret: u64,
+    /// Nombre de déplacements réussis.
+    pub moves: u32,
+    pub challenge: Option<RunningChallenge>,
+}
+
+/// État complet d'une partie : labyrinthe, équipes et joueurs.
+pub struct GameState {
+    maze: Maze,
+    config: GameConfig,
+    /// Équipes indexées par token d'inscription.
+    teams: HashMap<String, Team>,
+    players: HashMap<PlayerId, Player>,
+    next_player_id: PlayerId,
+    /// Messages en attente pour chaque joueur, envoyés avant la réponse à sa prochaine requête.
+    outbox: HashMap<PlayerId, Vec<Message>>,
+    monsters: Vec<Position>,
+    rng: ChaCha8Rng,
+}
+
+impl GameState {
+    pub fn new(maze: Maze, config: GameConfig) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+        let exit = maze.exit();
+        let free: Vec<Position> = maze.cells().filter(|&pos| pos != exit).collect();
+        let monsters = free.choose_multiple(&mut rng, config.monsters).copied().collect();
+        Self {
+            maze,
+            config,
+            teams: HashMap::new(),
+            players: HashMap::new(),
+            next_player_id: 0,
+            outbox: HashMap::new(),
+            monsters,
+            rng,
+        }
+    }
+
+    pub fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    pub fn player(&self, id: PlayerId) -> Option<&Player> {
+        self.players.get(&id)
+    }
+
+    pub fn team(&self, token: &str) -> Option<&Team> {
+        self.teams.get(token)
+    }
+
+    /// Positions des monstres.
+    pub fn monsters(&self) -> &[Position] {
+        &self.monsters
+    }
+
+    /// Inscrit une équipe et renvoie son token d'inscription.
+    pub fn register_team(&mut self, name: &str) -> Result<(u8, String), RegistrationError> {
+        if name.trim().is_empty() {
+            return Err(RegistrationError::InvalidName);
+        }
+        if self.teams.values().any(|t| t.name == name) {
+            return Err(RegistrationError::AlreadyRegistered);
+        }
+        let token = loop {
+            let candidate = format!("{:08X}", self.rng.gen::<u32>());
+            if !self.teams.contains_key(&candidate) {
+                break candidate;
+            }
+        };
+        self.teams.insert(token.clone(), Team {
+            name: name.to_string(),
+            expected_players: self.config.players_per_team,
+            players: Vec::new(),
+        });
+        Ok((self.config.players_per_team, token))
+    }
+
+    /// Inscrit un joueur dans l'équipe du token et le place sur une cellule libre.
+    pub fn subscribe_player(&mut self, name: &str, token: &str) -> Result<PlayerId, RegistrationError> {
+        if name.trim().is_empty() {
+            return Err(RegistrationError::InvalidName);
+        }
+        let team = self.teams.get(token).ok_or(RegistrationError::InvalidRegistrationToken)?;
+        if team.players.len() >= team.expected_players as usize {
+            return Err(RegistrationError::TooManyPlayers);
+        }
+        if team.players.iter().any(|id| self.players.get(id).is_some_and(|p| p.name == name)) {
+            return Err(RegistrationError::AlreadyRegistered);
+        }
+        let team_name = team.name.clone();
+
+        let exit = self.maze.exit();
+        let mut free: Vec<Position> = self.maze.cells()
+            .filter(|&pos| pos != exit && !self.is_occupied(pos))
+            .collect();
+        if free.is_empty() {
+            free = self.maze.cells().filter(|&pos| pos != exit).collect();
+        }
+        let position = *free.choose(&mut self.rng).unwrap_or(&exit);
+        let facing = Direction::ALL[self.rng.gen_range(0..4)];
+
+        let id = self.next_player_id;
+        self.next_player_id += 1;
+        self.players.insert(id, Player {
+            name: name.to_string(),
+            team: team_name,
+            position,
+            facing,
+            escaped: position == exit,
+            secret: 0,
+            moves: 0,
+            challenge: None,
+        });
+        if let Some(team) = self.teams.get_mut(token) {
+            team.players.push(id);
+        }
+        if self.config.hints.grid_size_at_start {
+            let grid_size = Hint::GridSize { columns: self.maze.columns() as u32, rows: self.maze.rows() as u32 };
+            self.push_message(id, Message::Hint(grid_size));
+        }
+        self.renew_secret(id);
+        Ok(id)
+    }
+
+    /// Retire un joueur dont la connexion a été fermée. Sa place dans l'équipe reste prise.
+    pub fn disconnect(&mut self, id: PlayerId) {
+        self.players.remove(&id);
+        self.outbox.remove(&id);
+    }
+
+    /// Vide et renvoie les messages en attente pour le joueur.
+    pub fn take_messages(&mut self, id: PlayerId) -> Vec<Message> {
+        self.outbox.remove(&id).unwrap_or_default()
+    }
+
+    fn push_message(&mut self, id: PlayerId, message: Message) {
+        self.outbox.entry(id).or_default().push(message);
+    }
+
+    /// Tire un nouveau secret pour le joueur et le lui annonce.
+    fn renew_secret(&mut self, id: PlayerId) {
+        let secret = self.rng.gen::<u64>();
+        if let Some(player) = self.players.get_mut(&id) {
+            player.secret = secret;
+            self.push_message(id, Message::Hint(Hint::Secret(secret)));
+        }
+    }
+
+    /// Un joueur ou un monstre se trouve sur la cellule.
+    fn is_occupied(&self, pos: Position) -> bool {
+        self.monsters.contains(&pos) || self.players.values().any(|p| p.position == pos)
+    }
+
+    /// Chaque monstre fait un pas au hasard vers une cellule libre, hors sortie, ou reste sur place.
+    fn move_monsters(&mut self) {
+        let exit = self.maze.exit();
+        for i in 0..self.monsters.len() {
+            let from = self.monsters[i];
+            let moves: Vec<Position> = Direction::ALL.into_iter()
+                .filter_map(|dir| self.maze.step(from, dir))
+                .filter(|&pos| pos != exit && !self.is_occupied(pos))
+                .collect();
+            if let Some(&to) = moves.choose(&mut self.rng) {
+                self.monsters[i] = to;
+            }
+        }
+    }
+
+    /// Coéquipiers connectés du joueur (lui exclu).
+    fn teammates(&self, id: PlayerId) -> Vec<PlayerId> {
+        let Some(player) = self.players.get(&id) else {
+            return Vec::new();
+        };
+        self.players.iter()
+            .filter(|(other_id, other)| **other_id != id && other.team == player.team)
+            .map(|(other_id, _)| *other_id)
+            .collect()
+    }
+
+    /// Déplace le joueur dans la direction relative à son orientation actuelle.
+    /// Un joueur dont un challenge est en cours doit d'abord le résoudre ; un adversaire ou
+    /// un monstre bloque le passage. Les monstres bougent ensuite, que le déplacement ait
+    /// réussi ou non : un joueur bloqué ne les fige pas.
+    pub fn move_player(&mut self, id: PlayerId, direction: &RelativeDirection) -> Result<(), ActionError> {
+        let result = self.step_player(id, direction);
+        self.move_monsters();
+        result
+    }
+
+    fn step_player(&mut self, id: PlayerId, direction: &RelativeDirection) -> Result<(), ActionError> {
+        let player = self.players.get(&id).expect("joueur inconnu");
+        if player.challenge.is_some() {
+            return Err(ActionError::SolveChallengeFirst);
+        }
+        let heading = player.facing.turn(direction);
+        let target = self.maze.step(player.position, heading).ok_or(ActionError::CannotPassThroughWall)?;
+        let team = &player.team;
+        if self.monsters.contains(&target) || self.players.values().any(|p| p.position == target && &p.team != team) {
+            return Err(ActionError::CannotPassThroughOpponent);
+        }
+        let exit = self.maze.exit();
+        let player = self.players.get_mut(&id).expect("joueur inconnu");
+        player.position = target;
+        player.facing = heading;
+        player.escaped |= target == exit;
+        player.moves += 1;
+        let moves = player.moves;
+
+        // Rejoindre un coéquipier en SOS le libère.
+        for mate in self.teammates(id) {
+            let player = self.players.get_mut(&mate).expect("joueur inconnu");
+            if player.position == target && player.challenge == Some(RunningChallenge::Sos) {
+                player.challenge = None;
+            }
+        }
+
+        if self.config.hints.secrets == SecretDelivery::EveryMove {
+            self.renew_secret(id);
+        }
+        if self.config.hints.compass_after(moves) {
+            let angle = hint_rules::relative_compass(target, heading, exit);
+            self.push_message(id, Message::Hint(Hint::RelativeCompass { angle }));
+        }
+        let modulo = self.rng.gen_range(2..=1000);
+        let teammates = self.teammates(id);
+        // Un SOS n'est lancé que si un coéquipier est libre de venir aider.
+        let has_helper = teammates.iter().any(|mate| self.players[mate].challenge != Some(RunningChallenge::Sos));
+        if let Some(running) = self.config.challenges.trigger(moves, modulo, has_helper) {
+            if self.config.hints.secrets == SecretDelivery::OnChallenge && running != RunningChallenge::Sos {
+                self.renew_secret(id);
+            }
+            self.players.get_mut(&id).expect("joueur inconnu").challenge = Some(running);
+            self.push_message(id, Message::Challenge(running.to_message()));
+            if running == RunningChallenge::Sos {
+                for mate in teammates {
+                    self.push_message(mate, Message::Hint(Hint::SOSHelper));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Vérifie la réponse au challenge en cours du joueur et le lève si elle est juste.
+    /// Un `SOS` ne se résout pas par une réponse : un coéquipier doit venir sur la cellule du joueur.
+    pub fn solve_challenge(&mut self, id: PlayerId, answer: &str) -> Result<(), ActionError> {
+        let player = self.players.get(&id).expect("joueur inconnu");
+        match player.challenge {
+            None => Err(ActionError::NoRunningChallenge),
+            Some(RunningChallenge::Sos) => Err(ActionError::InvalidChallengeSolution),
+            Some(RunningChallenge::SecretSumModulo(modulo)) => {
+                let secrets = self.players.values().filter(|p| p.team == player.team).map(|p| p.secret);
+                let expected = secret_sum_modulo(secrets, modulo);
+                if answer.trim().parse::<u64>() != Ok(expected) {
+                    return Err(ActionError::InvalidChallengeSolution);
+                }
+                self.players.get_mut(&id).expect("joueur inconnu").challenge = None;
+                Ok(())
+            }
+        }
+    }
+
+    /// Vue radar du joueur : 3x3 cellules centrées sur lui, orientées selon son regard.
+    pub fn radar_view(&self, id: PlayerId) -> RadarView {
+        let player = self.players.get(&id).expect("joueur inconnu");
+        let mut entities = Entities {
+            exit: Some(self.maze.exit()),
+            monsters: self.monsters.clone(),
+            ..Entities::default()
+        };
+        for (other_id, other) in &self.players {
+            if *other_id == id {
+                continue;
+            }
+            if other.team == player.team {
+                entities.allies.push(other.position);
+            } else {
+                entities.enemies.push(other.position);
+            }
+        }
+        radar::radar_view(&self.maze, player.position, player.facing, &entities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::radar::{Passage, RadarGrid, RadarItem};
+
+    fn corridor() -> Maze {
+        Maze::from_ascii(
+            "
+            +-+-+-+
+            |    E|
+            +-+-+-+
+            ",
+        )
+    }
+
+    fn place(state: &mut GameState, id: PlayerId, position: Position, facing: Direction) {
+        let player = state.players.get_mut(&id).unwrap();
+        player.position = position;
+        player.facing = facing;
+    }
+
+    #[test]
+    fn test_register_team() {
+        let mut state = GameState::new(corridor(), GameConfig::default());
+        let (expected, token_a) = state.register_team("alpha").unwrap();
+        assert_eq!(expected, 3);
+        let (_, token_b) = state.register_team("beta").unwrap();
+        assert_ne!(token_a, token_b);
+        assert_eq!(state.register_team("alpha"), Err(RegistrationError::AlreadyRegistered));
+        assert_eq!(state.register_team(" "), Err(RegistrationError::InvalidName));
+    }
+
+    #[test]
+    fn test_subscribe_player_limits() {
+        let config = GameConfig { players_per_team: 1, ..GameConfig::default() };
+        let mut state = GameState::new(corridor(), config);
+        let (_, token) = state.register_team("alpha").unwrap();
+        assert_eq!(state.subscribe_player("bob", "WRONG"), Err(RegistrationError::InvalidRegistrationToken));
+        let id = state.subscribe_player("bob", &token).unwrap();
+        assert_ne!(state.player(id).unwrap().position, state.maze().exit());
+        assert_eq!(state.subscribe_player("carol", &token), Err(RegistrationError::TooManyPlayers));
+    }
+
+    #[test]
+    fn test_move_relative_to_facing() {
+        let mut state = GameState::new(corridor(), GameConfig::default());
+        let (_, token) = state.register_team("alpha").unwrap();
+        let id = state.subscribe_player("bob", &token).unwrap();
+        place(&mut state, id, (0, 0), Direction::North);
+
+        assert_eq!(state.move_player(id, &RelativeDirection::Front), Err(ActionError::CannotPassThroughWall));
+        state.move_player(id, &RelativeDirection::Right).unwrap();
+        let player = state.player(id).unwrap();
+        assert_eq!((player.position, player.facing), ((0, 1), Direction::East));
+        state.move_player(id, &RelativeDirection::Front).unwrap();
+        assert!(state.player(id).unwrap().escaped);
+        state.move_player(id, &RelativeDirection::Back).unwrap();
+        assert_eq!(state.player(id).unwrap().facing, Direction::West);
+    }
+
+    #[test]
+    fn test_cannot_pass_through_opponent() {
+        let mut state = GameState::new(corridor(), GameConfig::default());
+        let (_, token_a) = state.register_team("alpha").unwrap();
+        let (_, token_b) = state.register_team("beta").unwrap();
+        let a = state.subscribe_player("a", &token_a).unwrap();
+        let b = state.subscribe_player("b", &token_b).unwrap();
+        let ally = state.subscribe_player("c", &token_a).unwrap();
+        place(&mut state, a, (0, 0), Direction::East);
+        place(&mut state, b, (0, 1), Direction::West);
+        place(&mut state, ally, (0, 2), Direction::West);
+
+        assert_eq!(state.move_player(a, &RelativeDirection::Front), Err(ActionError::CannotPassThroughOpponent));
+        state.disconnect(b);
+        state.move_player(a, &RelativeDirection::Front).unwrap();
+        state.move_player(a, &RelativeDirection::Front).unwrap();
+        assert_eq!(state.player(a).unwrap().position, (0, 2));
+    }
+
+    #[test]
+    fn test_monsters_block_and_wander() {
+        let config = GameConfig { monsters: 1, ..GameConfig::default() };
+        let mut state = GameState::new(Maze::open(3, 3), config);
+        assert_eq!(state.monsters().len(), 1);
+        let (_, token) = state.register_team("alpha").unwrap();
+        let id = state.subscribe_player("bob", &token).unwrap();
+        state.monsters = vec![(1, 1)];
+        place(&mut state, id, (1, 0), Direction::East);
+
+        let grid = RadarGrid::decode(&state.radar_view(id).0).unwrap();
+        assert_eq!(grid.item(0, 1), RadarItem::Monster);
+        assert_eq!(state.move_player(id, &RelativeDirection::Front), Err(ActionError::CannotPassThroughOpponent));
+
+        let monster = state.monsters()[0];
+        assert_ne!(monster, (1, 1));
+        assert_ne!(monster, state.player(id).unwrap().position);
+        assert_ne!(monster, state.maze().exit());
+    }
+
+    #[test]
+    fn test_sos_blocks_until_teammate_arrives() {
+        let config = GameConfig {
+            challenges: ChallengeRules { secret_sum_every: None, sos_every: Some(1) },
+            ..GameConfig::default()
+        };
+        let mut state = GameState::new(corridor(), config);
+        let (_, token) = state.register_team("alpha").unwrap();
+        let a = state.subscribe_player("a", &token).unwrap();
+        let b = state.subscribe_player("b", &token).unwrap();
+        place(&mut state, a, (0, 0), Direction::East);
+        place(&mut state, b, (0, 2), Direction::West);
+        state.take_messages(a);
+        state.take_messages(b);
+
+        state.move_player(a, &RelativeDirection::Front).unwrap();
+        assert_eq!(state.player(a).unwrap().challenge, Some(RunningChallenge::Sos));
+        assert!(state.take_messages(a).contains(&Message::Challenge(shared::messages::Challenge::SOS)));
+        assert_eq!(state.take_messages(b), vec![Message::Hint(Hint::SOSHelper)]);
+        assert_eq!(state.move_player(a, &RelativeDirection::Front), Err(ActionError::SolveChallengeFirst));
+        assert_eq!(state.solve_challenge(a, "0"), Err(ActionError::InvalidChallengeSolution));
+
+        // b rejoint a : a est libéré, b reçoit à son tour un SOS.
+        state.move_player(b, &RelativeDirection::Front).unwrap();
+        assert_eq!(state.player(a).unwrap().challenge, None);
+        assert_eq!(state.player(b).unwrap().challenge, Some(RunningChallenge::Sos));
+        // Plus aucun coéquipier libre : a ne reçoit pas de SOS.
+        state.move_player(a, &RelativeDirection::Back).unwrap();
+        assert_eq!(state.player(a).unwrap().challenge, None);
+    }
+
+    #[test]
+    fn test_hint_rules() {
+        let config = GameConfig {
+            challenges: ChallengeRules { secret_sum_every: Some(2), sos_every: None },
+            hints: HintRules { compass_every: Some(1), grid_size_at_start: true, secrets: SecretDelivery::OnChallenge },
+            ..GameConfig::default()
+        };
+        let mut state = GameState::new(corridor(), config);
+        let (_, token) = state.register_team("alpha").unwrap();
+        let id = state.subscribe_player("bob", &token).unwrap();
+        let messages = state.take_messages(id);
+        assert_eq!(messages[0], Message::Hint(Hint::GridSize { columns: 3, rows: 1 }));
+        assert!(matches!(messages[1], Message::Hint(Hint::Secret(_))));
+        place(&mut state, id, (0, 0), Direction::North);
+
+        state.move_player(id, &RelativeDirection::Right).unwrap();
+        assert_eq!(state.take_messages(id), vec![Message::Hint(Hint::RelativeCompass { angle: 0.0 })]);
+        state.move_player(id, &RelativeDirection::Back).unwrap();
+        let messages = state.take_messages(id);
+        assert_eq!(messages[0], Message::Hint(Hint::RelativeCompass { angle: 180.0 }));
+        assert!(matches!(messages[1], Message::Hint(Hint::Secret(_))));
+        assert!(matches!(messages[2], Message::Challenge(_)));
+    }
+
+    #[test]
+    fn test_radar_view_is_rotated() {
+        let mut state = GameState::new(corridor(), GameConfig::default());
+        let (_, token) = state.register_team("alpha").unwrap();
+        let id = state.subscribe_player("bob", &token).unwrap();
+        place(&mut state, id, (0, 1), Direction::East);
+
+        let grid = RadarGrid::decode(&state.radar_view(id).0).unwrap();
+        assert_eq!(grid.player_passage(RelativeDirection::Front), Passage::Open);
+        assert_eq!(grid.player_passage(RelativeDirection::Back), Passage::Open);
+        assert_eq!(grid.player_passage(RelativeDirection::Left), Passage::Wall);
+        assert_eq!(grid.player_passage(RelativeDirection::Right), Passage::Wall);
+        assert_eq!(grid.item(0, 1), RadarItem::Target);
+        assert_eq!(grid.horizontal(0, 0), Passage::Undefined);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::maze::{Direction, Position};
+
+/// Moment où un joueur reçoit un nouveau secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SecretDelivery {
+    /// Après chaque déplacement réussi.
+    #[default]
+    EveryMove,
+    /// Seulement quand un `SecretSumModulo` lui est lancé.
+    OnChallenge,
+}
+
+/// Règles d'envoi des indices. Un secret est toujours envoyé à l'inscription.
+#[derive(Debug, Clone, Default)]
+pub struct HintRules {
+    /// Une boussole vers la sortie tous les N déplacements (jamais si `None`).
+    pub compass_every: Option<u32>,
+    /// Envoie la taille du labyrinthe à l'inscription.
+    pub grid_size_at_start: bool,
+    pub secrets: SecretDelivery,
+}
+
+impl HintRules {
+    pub fn compass_after(&self, moves: u32) -> bool {
+        self.compass_every.is_some_and(|n| n > 0 && moves.is_multiple_of(n))
+    }
+}
+
+fn heading_degrees(facing: Direction) -> f32 {
+    match facing {
+        Direction::North => 0.0,
+        Direction::East => 90.0,
+        Direction::South => 180.0,
+        Direction::West => 270.0,
+    }
+}
+
+/// Angle de `target` vu depuis `from` par un joueur regardant vers `facing`,
+/// en degrés dans [0, 360) dans le sens horaire : 0 devant, 90 à droite.
+pub fn relative_compass(from: Position, facing: Direction, target: Position) -> f32 {
+    let dr = target.0 as f32 - from.0 as f32;
+    let dc = target.1 as f32 - from.1 as f32;
+    // Cap absolu depuis le nord, sens horaire (les lignes croissent vers le sud).
+    let absolute = dc.atan2(-dr).to_degrees();
+    (absolute - heading_degrees(facing)).rem_euclid(360.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_compass() {
+        assert_eq!(relative_compass((2, 2), Direction::North, (0, 2)), 0.0);
+        assert_eq!(relative_compass((2, 2), Direction::North, (2, 5)), 90.0);
+        assert_eq!(relative_compass((2, 2), Direction::East, (2, 5)), 0.0);
+        assert_eq!(relative_compass((2, 2), Direction::East, (0, 2)), 270.0);
+        assert_eq!(relative_compass((2, 2), Direction::West, (4, 4)), 225.0);
+    }
+}
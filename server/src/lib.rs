@@ -0,0 +1,6 @@
+pub mod challenge;
+pub mod game;
+pub mod hints;
+pub mod maze;
+pub mod network;
+pub mod radar;
@@ -1,97 +1,89 @@
-use std::io::{Read, Write};
-use std::net::{TcpListener, TcpStream};
-use std::thread;
-use log::{info, warn};
-use serde_json;
-use shared::messages::{RegisterTeamResultWrapper, SubscribePlayer};
+use std::env;
+use std::future;
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use log::{error, info, warn};
+use server::challenge::ChallengeRules;
+use server::game::{GameConfig, GameState};
+use server::hints::HintRules;
+use server::maze::generators::Algorithm;
+use server::network::{serve, ServerConfig};
+use shared::config::{self, ServerAddress};
+use tokio::net::TcpListener;
+use tokio::signal;
 
-fn handle_client(mut stream: TcpStream) {
-    loop {
-        let mut size_buffer = [0u8; 4];
-        if let Err(e) = stream.read_exact(&mut size_buffer) {
-            warn!("Erreur lors de la lecture de la taille du message: {}", e);
-            break;
-        }
-        let msg_size = u32::from_le_bytes(size_buffer) as usize;
-        if msg_size > 1_048_576 {
-            warn!("Too large message size: {}", msg_size);
-            break;
-        }
-        let mut buffer = vec![0u8; msg_size];
-        if let Err(e) = stream.read_exact(&mut buffer) {
-            warn!("Erreur lors de la lecture du message: {}", e);
-            break;
-        }
-        let msg_str = String::from_utf8_lossy(&buffer);
-        info!("Message reçu: {}", msg_str);
+/// Options propres au serveur, en plus de celles de l'adresse.
+const USAGE: &str = "[--max-connections N] [--read-timeout SECONDES] [--write-timeout SECONDES]";
 
-        if msg_str.contains("\"RegisterTeam\"") {
-            info!("RegisterTeam reçu.");
-            let response = serde_json::json!({
-                "RegisterTeamResult": {
-                    "Ok": {
-                        "expected_players": 3,
-                        "registration_token": "TESTTOKEN123"
-                    }
-                }
-            }).to_string();
-            let response_bytes = response.as_bytes();
-            let response_size = (response_bytes.len() as u32).to_le_bytes();
-            if stream.write_all(&response_size).is_err() {
-                warn!("Erreur lors de l'envoi de la taille de réponse RegisterTeam");
-                break;
-            }
-            if stream.write_all(response_bytes).is_err() {
-                warn!("Erreur lors de l'envoi de la réponse RegisterTeam");
-                break;
-            }
-        } else if msg_str.contains("\"SubscribePlayer\"") {
-            let response = serde_json::json!({
-                "SubscribePlayerResult": "Ok"
-            }).to_string();
-            let response_bytes = response.as_bytes();
-            let response_size = (response_bytes.len() as u32).to_le_bytes();
-            if stream.write_all(&response_size).is_err() {
-                warn!("Erreur lors de l'envoi de la taille de réponse SubscribePlayer");
-                break;
-            }
-            if stream.write_all(response_bytes).is_err() {
-                warn!("Erreur lors de l'envoi de la réponse SubscribePlayer");
-                break;
-            }
-        } else {
-            let response = serde_json::json!({
-                "ActionError": "Unknown message"
-            }).to_string();
-            let response_bytes = response.as_bytes();
-            let response_size = (response_bytes.len() as u32).to_le_bytes();
-            if stream.write_all(&response_size).is_err() {
-                warn!("Erreur lors de l'envoi de la taille de réponse (Unknown message)");
-                break;
-            }
-            if stream.write_all(response_bytes).is_err() {
-                warn!("Erreur lors de l'envoi de la réponse (Unknown message)");
-                break;
+fn parse_args(args: impl Iterator<Item = String>) -> Result<(ServerAddress, ServerConfig), String> {
+    let (address, rest) = ServerAddress::from_env_and_args(args).map_err(|e| e.to_string())?;
+    let mut server = ServerConfig::default();
+    let mut args = rest.into_iter();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("valeur manquante pour {}", flag));
+        let seconds = |value: String| value.parse().map(Duration::from_secs).map_err(|e| format!("{}: {}", flag, e));
+        match flag.as_str() {
+            "--max-connections" => {
+                server.max_connections = value()?.parse().map_err(|e| format!("--max-connections: {}", e))?
             }
+            "--read-timeout" => server.read_timeout = seconds(value()?)?,
+            "--write-timeout" => server.write_timeout = seconds(value()?)?,
+            other => return Err(format!("option inconnue: {}", other)),
         }
     }
+    Ok((address, server))
 }
 
-fn main() {
-    env_logger::init();
-    let listener = TcpListener::bind("127.0.0.1:8778").expect("Impossible de lier sur l'adresse");
-    info!("Serveur lancé sur 127.0.0.1:8778");
+/// Se termine au second Ctrl-C : le premier demande un arrêt propre à `serve`.
+async fn forced_stop() {
+    for _ in 0..2 {
+        if signal::ctrl_c().await.is_err() {
+            future::pending::<()>().await;
+        }
+    }
+}
 
-    for stream in listener.incoming() {
-        match stream {
-            Ok(stream) => {
-                thread::spawn(|| {
-                    handle_client(stream);
-                });
-            }
-            Err(e) => {
-                warn!("Échec d'une connexion: {}", e);
-            }
+#[tokio::main]
+async fn main() -> ExitCode {
+    env_logger::init();
+    let (address, server_config) = match parse_args(env::args().skip(1)) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}\nUsage: server {} {}", e, config::USAGE, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let config = GameConfig {
+        challenges: ChallengeRules { secret_sum_every: Some(10), sos_every: Some(25) },
+        hints: HintRules { compass_every: Some(5), grid_size_at_start: true, ..HintRules::default() },
+        monsters: 3,
+        ..GameConfig::default()
+    };
+    let maze = Algorithm::RecursiveBacktracker.generate(16, 10, config.seed);
+    info!("Labyrinthe {} généré (graine {}):\n{}", Algorithm::RecursiveBacktracker, config.seed, maze.to_ascii());
+    info!("Carte encodée: {}", maze.encode());
+    let game = GameState::new(maze, config);
+    let listener = match TcpListener::bind(address.to_string()).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Impossible d'écouter sur {}: {}", address, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    info!("Serveur lancé sur {} ({} connexions au plus)", address, server_config.max_connections);
+    let shutdown = async {
+        if let Err(e) = signal::ctrl_c().await {
+            error!("Impossible d'écouter SIGINT: {}", e);
+            future::pending::<()>().await;
+        }
+        info!("SIGINT reçu: arrêt à la fin des parties en cours (Ctrl-C à nouveau pour forcer)");
+    };
+    tokio::select! {
+        _ = serve(listener, Arc::new(Mutex::new(game)), server_config, shutdown) => ExitCode::SUCCESS,
+        _ = forced_stop() => {
+            warn!("Arrêt forcé: des parties étaient encore en cours");
+            ExitCode::FAILURE
         }
     }
 }
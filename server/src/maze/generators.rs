@@ -0,0 +1,334 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{Direction, Maze, Position};
+
+/// Algorithmes de génération disponibles. Tous sont déterministes pour une graine donnée.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+    Eller,
+    /// Labyrinthe parfait dont les culs-de-sac sont ouverts, ce qui crée des boucles.
+    Braided,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 5] = [
+        Algorithm::RecursiveBacktracker,
+        Algorithm::Prim,
+        Algorithm::Kruskal,
+        Algorithm::Eller,
+        Algorithm::Braided,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::RecursiveBacktracker => "backtracker",
+            Algorithm::Prim => "prim",
+            Algorithm::Kruskal => "kruskal",
+            Algorithm::Eller => "eller",
+            Algorithm::Braided => "braided",
+        }
+    }
+
+    /// Génère un labyrinthe de `width` colonnes et `height` lignes.
+    pub fn generate(self, width: usize, height: usize, seed: u64) -> Maze {
+        match self {
+            Algorithm::RecursiveBacktracker => recursive_backtracker(width, height, seed),
+            Algorithm::Prim => prim(width, height, seed),
+            Algorithm::Kruskal => kruskal(width, height, seed),
+            Algorithm::Eller => eller(width, height, seed),
+            Algorithm::Braided => braided(width, height, seed),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Algorithm::ALL
+            .into_iter()
+            .find(|a| a.name() == s)
+            .ok_or_else(|| format!("algorithme de génération inconnu: {}", s))
+    }
+}
+
+/// Place la sortie sur une cellule tirée avec le générateur de la graine.
+fn with_random_exit(mut maze: Maze, rng: &mut ChaCha8Rng) -> Maze {
+    let exit = (rng.gen_range(0..maze.rows()), rng.gen_range(0..maze.columns()));
+    maze.set_exit(exit);
+    maze
+}
+
+/// Exploration en profondeur avec retour arrière : longs couloirs, peu d'embranchements.
+pub fn recursive_backtracker(width: usize, height: usize, seed: u64) -> Maze {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut maze = Maze::closed(height, width);
+    let mut visited = vec![vec![false; width]; height];
+    let start = (rng.gen_range(0..height), rng.gen_range(0..width));
+    visited[start.0][start.1] = true;
+    let mut stack = vec![start];
+    while let Some(&current) = stack.last() {
+        let candidates: Vec<Direction> = Direction::ALL
+            .into_iter()
+            .filter(|&d| maze.neighbour(current, d).is_some_and(|(r, c)| !visited[r][c]))
+            .collect();
+        match candidates.choose(&mut rng) {
+            Some(&dir) => {
+                let next = maze.neighbour(current, dir).expect("voisin vérifié");
+                maze.set_wall(current, dir, false);
+                visited[next.0][next.1] = true;
+                stack.push(next);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+    with_random_exit(maze, &mut rng)
+}
+
+/// Prim randomisé : on ouvre un mur tiré au hasard à la frontière de la zone déjà creusée.
+pub fn prim(width: usize, height: usize, seed: u64) -> Maze {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut maze = Maze::closed(height, width);
+    let mut in_maze = vec![vec![false; width]; height];
+    let start = (rng.gen_range(0..height), rng.gen_range(0..width));
+    in_maze[start.0][start.1] = true;
+    let mut frontier: Vec<(Position, Direction)> = Direction::ALL.into_iter().map(|d| (start, d)).collect();
+    while !frontier.is_empty() {
+        let (cell, dir) = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+        let Some(next) = maze.neighbour(cell, dir) else { continue };
+        if in_maze[next.0][next.1] {
+            continue;
+        }
+        maze.set_wall(cell, dir, false);
+        in_maze[next.0][next.1] = true;
+        frontier.extend(Direction::ALL.into_iter().map(|d| (next, d)));
+    }
+    with_random_exit(maze, &mut rng)
+}
+
+/// Kruskal randomisé : les murs sont examinés dans un ordre aléatoire et ouverts
+/// lorsqu'ils séparent deux composantes distinctes.
+pub fn kruskal(width: usize, height: usize, seed: u64) -> Maze {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut maze = Maze::closed(height, width);
+    let mut edges = Vec::new();
+    for pos in maze.cells() {
+        if pos.0 + 1 < height {
+            edges.push((pos, Direction::South));
+        }
+        if pos.1 + 1 < width {
+            edges.push((pos, Direction::East));
+        }
+    }
+    edges.shuffle(&mut rng);
+
+    let mut parent: Vec<usize> = (0..width * height).collect();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for (cell, dir) in edges {
+        let next = maze.neighbour(cell, dir).expect("arête intérieure");
+        let a = find(&mut parent, cell.0 * width + cell.1);
+        let b = find(&mut parent, next.0 * width + next.1);
+        if a != b {
+            parent[a] = b;
+            maze.set_wall(cell, dir, false);
+        }
+    }
+    with_random_exit(maze, &mut rng)
+}
+
+/// Algorithme d'Eller : construction ligne par ligne en ne gardant que les ensembles de la ligne courante.
+pub fn eller(width: usize, height: usize, seed: u64) -> Maze {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut maze = Maze::closed(height, width);
+    let mut sets: Vec<Option<usize>> = vec![None; width];
+    let mut next_set = 0;
+    for r in 0..height {
+        for set in sets.iter_mut() {
+            if set.is_none() {
+                *set = Some(next_set);
+                next_set += 1;
+            }
+        }
+        let last_row = r + 1 == height;
+        // Fusions horizontales (forcées sur la dernière ligne pour tout relier).
+        for c in 0..width.saturating_sub(1) {
+            let (a, b) = (sets[c], sets[c + 1]);
+            if a != b && (last_row || rng.gen_bool(0.5)) {
+                maze.set_wall((r, c), Direction::East, false);
+                for set in sets.iter_mut() {
+                    if *set == b {
+                        *set = a;
+                    }
+                }
+            }
+        }
+        if last_row {
+            break;
+        }
+        // Au moins une ouverture vers le bas par ensemble.
+        let mut next_sets = vec![None; width];
+        let mut ids: Vec<usize> = sets.iter().flatten().copied().collect();
+        ids.sort_unstable();
+        ids.dedup();
+        for id in ids {
+            let mut members: Vec<usize> = (0..width).filter(|&c| sets[c] == Some(id)).collect();
+            members.shuffle(&mut rng);
+            for (i, &c) in members.iter().enumerate() {
+                if i == 0 || rng.gen_bool(0.5) {
+                    maze.set_wall((r, c), Direction::South, false);
+                    next_sets[c] = Some(id);
+                }
+            }
+        }
+        sets = next_sets;
+    }
+    with_random_exit(maze, &mut rng)
+}
+
+/// Labyrinthe « tressé » : un labyrinthe parfait dont chaque cul-de-sac est ouvert vers un voisin,
+/// de préférence lui-même en cul-de-sac. Il existe alors plusieurs chemins entre deux cellules.
+pub fn braided(width: usize, height: usize, seed: u64) -> Maze {
+    let mut maze = recursive_backtracker(width, height, seed);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed ^ 0xB4A1_DED0);
+    let is_dead_end = |maze: &Maze, pos: Position| {
+        Direction::ALL.into_iter().filter(|&d| maze.is_wall(pos, d)).count() == 3
+    };
+    let cells: Vec<Position> = maze.cells().collect();
+    for pos in cells {
+        if !is_dead_end(&maze, pos) {
+            continue;
+        }
+        let closed: Vec<Direction> = Direction::ALL
+            .into_iter()
+            .filter(|&d| maze.is_wall(pos, d) && maze.neighbour(pos, d).is_some())
+            .collect();
+        let preferred: Vec<Direction> = closed
+            .iter()
+            .copied()
+            .filter(|&d| maze.neighbour(pos, d).is_some_and(|n| is_dead_end(&maze, n)))
+            .collect();
+        let choice = if preferred.is_empty() { closed.choose(&mut rng) } else { preferred.choose(&mut rng) };
+        if let Some(&dir) = choice {
+            maze.set_wall(pos, dir, false);
+        }
+    }
+    maze
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    fn reachable_cells(maze: &Maze) -> usize {
+        let mut seen = vec![vec![false; maze.columns()]; maze.rows()];
+        let mut queue = VecDeque::from([(0, 0)]);
+        seen[0][0] = true;
+        let mut count = 0;
+        while let Some(pos) = queue.pop_front() {
+            count += 1;
+            for dir in Direction::ALL {
+                if let Some(next) = maze.step(pos, dir) {
+                    if !seen[next.0][next.1] {
+                        seen[next.0][next.1] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    fn open_passages(maze: &Maze) -> usize {
+        maze.cells()
+            .map(|pos| [Direction::East, Direction::South].into_iter().filter(|&d| maze.step(pos, d).is_some()).count())
+            .sum()
+    }
+
+    #[test]
+    fn test_same_seed_same_maze() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(algorithm.generate(12, 9, 7), algorithm.generate(12, 9, 7), "{}", algorithm);
+            assert_ne!(algorithm.generate(12, 9, 7), algorithm.generate(12, 9, 8), "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_perfect_mazes_are_spanning_trees() {
+        let perfect = [Algorithm::RecursiveBacktracker, Algorithm::Prim, Algorithm::Kruskal, Algorithm::Eller];
+        for algorithm in perfect {
+            for seed in 0..20 {
+                let maze = algorithm.generate(11, 7, seed);
+                assert_eq!(reachable_cells(&maze), 77, "{} seed {}", algorithm, seed);
+                assert_eq!(open_passages(&maze), 76, "{} seed {}", algorithm, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_braided_has_loops_and_no_dead_ends() {
+        for seed in 0..20 {
+            let maze = braided(10, 10, seed);
+            assert_eq!(reachable_cells(&maze), 100);
+            assert!(open_passages(&maze) > 99);
+            for pos in maze.cells() {
+                let walls = Direction::ALL.into_iter().filter(|&d| maze.is_wall(pos, d)).count();
+                assert!(walls < 3, "cul-de-sac en {:?} (seed {})", pos, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_degenerate_sizes() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(reachable_cells(&algorithm.generate(1, 1, 3)), 1);
+            assert_eq!(reachable_cells(&algorithm.generate(5, 1, 3)), 5);
+            assert_eq!(reachable_cells(&algorithm.generate(1, 5, 3)), 5);
+        }
+    }
+
+    #[test]
+    fn test_algorithm_names() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(algorithm.name().parse::<Algorithm>(), Ok(algorithm));
+        }
+        assert!("dfs".parse::<Algorithm>().is_err());
+    }
+
+    #[test]
+    fn test_golden_backtracker() {
+        // Fige la sortie du générateur : une graine citée dans un rapport de bug doit rester valable.
+        let expected = "\
++-+-+-+-+-+
+|  E      |
++ +-+-+ +-+
+| |   |   |
++ + +-+-+ +
+|   |     |
++-+-+-+-+-+
+";
+        assert_eq!(recursive_backtracker(5, 3, 42).to_ascii(), expected);
+    }
+}
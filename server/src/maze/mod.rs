@@ -0,0 +1,268 @@
+pub mod generators;
+
+use shared::maze::Maze as EdgeMaze;
+use shared::radar::Passage;
+
+pub use shared::maze::{Direction, Position};
+
+/// Labyrinthe dont les murs sont portés par les arêtes entre cellules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maze {
+    rows: usize,
+    columns: usize,
+    /// Murs horizontaux : `horizontal[r * columns + c]` est le mur au nord de (r, c), `r` dans `0..=rows`.
+    horizontal: Vec<bool>,
+    /// Murs verticaux : `vertical[r * (columns + 1) + c]` est le mur à l'ouest de (r, c), `c` dans `0..=columns`.
+    vertical: Vec<bool>,
+    exit: Position,
+}
+
+impl Maze {
+    /// Labyrinthe dont toutes les cellules sont fermées. La sortie est placée au coin opposé à l'origine.
+    pub fn closed(rows: usize, columns: usize) -> Self {
+        assert!(rows > 0 && columns > 0, "un labyrinthe a au moins une cellule");
+        Self {
+            rows,
+            columns,
+            horizontal: vec![true; (rows + 1) * columns],
+            vertical: vec![true; rows * (columns + 1)],
+            exit: (rows - 1, columns - 1),
+        }
+    }
+
+    /// Labyrinthe sans aucun mur intérieur.
+    pub fn open(rows: usize, columns: usize) -> Self {
+        let mut maze = Self::closed(rows, columns);
+        for r in 0..rows {
+            for c in 0..columns {
+                if r + 1 < rows {
+                    maze.set_wall((r, c), Direction::South, false);
+                }
+                if c + 1 < columns {
+                    maze.set_wall((r, c), Direction::East, false);
+                }
+            }
+        }
+        maze
+    }
+
+    /// Construit un labyrinthe depuis un dessin ASCII : les lignes paires portent les murs horizontaux
+    /// (`-` pour un mur), les lignes impaires les murs verticaux (`|`) et les cellules (`E` pour la sortie).
+    ///
+    /// ```text
+    /// +-+-+
+    /// |  E|
+    /// + +-+
+    /// |   |
+    /// +-+-+
+    /// ```
+    pub fn from_ascii(drawing: &str) -> Self {
+        let lines: Vec<&[u8]> = drawing.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::as_bytes).collect();
+        assert!(lines.len() >= 3 && lines.len() % 2 == 1, "dessin de labyrinthe invalide");
+        let rows = lines.len() / 2;
+        let columns = lines[0].len() / 2;
+        let mut maze = Self::closed(rows, columns);
+        let at = |line: &[u8], i: usize| line.get(i).copied().unwrap_or(b' ');
+        for r in 0..=rows {
+            for c in 0..columns {
+                maze.horizontal[r * columns + c] = at(lines[2 * r], 2 * c + 1) == b'-';
+            }
+        }
+        for r in 0..rows {
+            for c in 0..=columns {
+                maze.vertical[r * (columns + 1) + c] = at(lines[2 * r + 1], 2 * c) == b'|';
+            }
+            for c in 0..columns {
+                if at(lines[2 * r + 1], 2 * c + 1) == b'E' {
+                    maze.exit = (r, c);
+                }
+            }
+        }
+        maze
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn exit(&self) -> Position {
+        self.exit
+    }
+
+    pub fn set_exit(&mut self, exit: Position) {
+        assert!(self.contains(exit), "la sortie doit être dans le labyrinthe");
+        self.exit = exit;
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        pos.0 < self.rows && pos.1 < self.columns
+    }
+
+    /// Indique s'il y a un mur du côté `dir` de la cellule `pos`. Les bords du labyrinthe sont des murs.
+    pub fn is_wall(&self, pos: Position, dir: Direction) -> bool {
+        let (r, c) = pos;
+        match dir {
+            Direction::North => self.horizontal[r * self.columns + c],
+            Direction::South => self.horizontal[(r + 1) * self.columns + c],
+            Direction::West => self.vertical[r * (self.columns + 1) + c],
+            Direction::East => self.vertical[r * (self.columns + 1) + c + 1],
+        }
+    }
+
+    /// Pose ou retire le mur du côté `dir` de la cellule `pos` (le mur est partagé avec la cellule voisine).
+    pub fn set_wall(&mut self, pos: Position, dir: Direction, wall: bool) {
+        let (r, c) = pos;
+        let slot = match dir {
+            Direction::North | Direction::South => {
+                let row = if dir == Direction::North { r } else { r + 1 };
+                &mut self.horizontal[row * self.columns + c]
+            }
+            Direction::West | Direction::East => {
+                let col = if dir == Direction::West { c } else { c + 1 };
+                &mut self.vertical[r * (self.columns + 1) + col]
+            }
+        };
+        *slot = wall;
+    }
+
+    /// Cellule voisine dans la direction `dir`, si elle est dans le labyrinthe (murs ignorés).
+    pub fn neighbour(&self, pos: Position, dir: Direction) -> Option<Position> {
+        let (dr, dc) = dir.as_offset();
+        let r = pos.0 as i64 + dr;
+        let c = pos.1 as i64 + dc;
+        if r < 0 || c < 0 || r as usize >= self.rows || c as usize >= self.columns {
+            None
+        } else {
+            Some((r as usize, c as usize))
+        }
+    }
+
+    /// Cellule atteinte en quittant `pos` par `dir`, si aucun mur ne bloque le passage.
+    pub fn step(&self, pos: Position, dir: Direction) -> Option<Position> {
+        if self.is_wall(pos, dir) {
+            None
+        } else {
+            self.neighbour(pos, dir)
+        }
+    }
+
+    /// Dessin ASCII du labyrinthe, au format accepté par `from_ascii`.
+    pub fn to_ascii(&self) -> String {
+        let mut out = String::new();
+        for r in 0..=self.rows {
+            for c in 0..self.columns {
+                out.push('+');
+                out.push(if self.horizontal[r * self.columns + c] { '-' } else { ' ' });
+            }
+            out.push_str("+\n");
+            if r == self.rows {
+                break;
+            }
+            for c in 0..=self.columns {
+                out.push(if self.vertical[r * (self.columns + 1) + c] { '|' } else { ' ' });
+                if c < self.columns {
+                    out.push(if (r, c) == self.exit { 'E' } else { ' ' });
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Passages du labyrinthe, tous connus : ouverts ou murs.
+    pub fn edges(&self) -> EdgeMaze {
+        let passage = |&wall: &bool| if wall { Passage::Wall } else { Passage::Open };
+        EdgeMaze::from_passages(
+            self.rows,
+            self.columns,
+            self.horizontal.iter().map(passage).collect(),
+            self.vertical.iter().map(passage).collect(),
+        )
+    }
+
+    /// Labyrinthe du serveur construit à partir de passages ; un passage inconnu est un mur.
+    pub fn from_edges(edges: &EdgeMaze, exit: Position) -> Self {
+        let wall = |p: &Passage| *p != Passage::Open;
+        let mut maze = Self::closed(edges.rows(), edges.columns());
+        maze.horizontal = edges.horizontal_passages().iter().map(wall).collect();
+        maze.vertical = edges.vertical_passages().iter().map(wall).collect();
+        maze.set_exit(exit);
+        maze
+    }
+
+    /// Encode le labyrinthe au format `encode_labyrinth` (passages sur 2 bits).
+    pub fn encode(&self) -> String {
+        self.edges().encode()
+    }
+
+    /// Itère sur toutes les cellules, ligne par ligne.
+    pub fn cells(&self) -> impl Iterator<Item = Position> {
+        let columns = self.columns;
+        (0..self.rows).flat_map(move |r| (0..columns).map(move |c| (r, c)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_ascii() {
+        let maze = Maze::from_ascii(
+            "
+            +-+-+
+            |  E|
+            + +-+
+            |   |
+            +-+-+
+            ",
+        );
+        assert_eq!((maze.rows(), maze.columns()), (2, 2));
+        assert_eq!(maze.exit(), (0, 1));
+        assert!(!maze.is_wall((0, 0), Direction::East));
+        assert!(!maze.is_wall((0, 1), Direction::West));
+        assert!(!maze.is_wall((0, 0), Direction::South));
+        assert!(maze.is_wall((0, 1), Direction::South));
+        assert!(maze.is_wall((1, 1), Direction::North));
+        assert!(maze.is_wall((1, 1), Direction::East));
+        assert_eq!(maze.step((0, 0), Direction::East), Some((0, 1)));
+        assert_eq!(maze.step((0, 0), Direction::North), None);
+    }
+
+    #[test]
+    fn test_ascii_round_trip() {
+        let maze = generators::kruskal(6, 4, 11);
+        assert_eq!(Maze::from_ascii(&maze.to_ascii()), maze);
+    }
+
+    #[test]
+    fn test_encode_passage_counts() {
+        let maze = Maze::open(3, 5);
+        assert_eq!(maze.edges().horizontal_passages().len(), 4 * 5);
+        assert_eq!(maze.edges().vertical_passages().len(), 3 * 6);
+        // 4 octets d'en-tête + 5 octets horizontaux + 5 octets verticaux.
+        assert_eq!(shared::encodings::b64::decode(&maze.encode()).unwrap().len(), 14);
+    }
+
+    #[test]
+    fn test_edges_round_trip() {
+        let maze = generators::kruskal(7, 5, 3);
+        let decoded = EdgeMaze::decode(&maze.encode()).unwrap();
+        assert_eq!(decoded, maze.edges());
+        assert_eq!(Maze::from_edges(&decoded, maze.exit()), maze);
+    }
+
+    #[test]
+    fn test_open_maze_keeps_border() {
+        let maze = Maze::open(3, 4);
+        for pos in maze.cells() {
+            for dir in Direction::ALL {
+                assert_eq!(maze.is_wall(pos, dir), maze.neighbour(pos, dir).is_none());
+            }
+        }
+    }
+}
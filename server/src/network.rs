@@ -0,0 +1,541 @@
+use std::future::{self, Future};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use log::{info, warn};
+use shared::messages::{
+    Action,
+    Message,
+    RegisterTeamResult,
+    RegisterTeamResultOk,
+    SubscribePlayerResult,
+};
+use shared::transport::{encode_frame, frame_size, TransportError, DEFAULT_MAX_FRAME_SIZE};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot, watch, Semaphore};
+use tokio::time::timeout;
+
+use crate::game::{GameState, PlayerId, RegistrationError};
+
+/// État de partie partagé entre toutes les connexions.
+/// Le verrou n'est jamais tenu pendant une attente : un `Mutex` standard suffit.
+pub type SharedGame = Arc<Mutex<GameState>>;
+
+/// Limites appliquées par le serveur à chaque connexion.
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Nombre maximal de connexions simultanées ; au-delà, les nouvelles connexions sont fermées dès leur acceptation.
+    pub max_connections: usize,
+    /// Durée maximale d'attente de la requête suivante d'un client avant de fermer sa connexion.
+    pub read_timeout: Duration,
+    /// Durée maximale d'une écriture sur la socket, et de l'attente d'une place dans la file d'envoi.
+    pub write_timeout: Duration,
+    /// Nombre de messages en attente d'envoi par connexion. File pleine, le serveur cesse de lire
+    /// les requêtes du client tant qu'il ne consomme pas ses réponses (RadarView comprises).
+    pub outgoing_queue: usize,
+    pub max_frame_size: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 4096,
+            read_timeout: Duration::from_secs(300),
+            write_timeout: Duration::from_secs(10),
+            outgoing_queue: 32,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+        }
+    }
+}
+
+/// Applique un message reçu à la partie et renvoie les réponses à envoyer, dans l'ordre.
+/// `player` est le joueur de la connexion, fixé par un `SubscribePlayer` accepté.
+/// `None` : le message n'a pas de sens sur cette connexion, qui doit être fermée
+/// plutôt que de laisser le client attendre une réponse qui ne viendra pas.
+fn dispatch(game: &mut GameState, message: Message, player: &mut Option<PlayerId>) -> Option<Vec<Message>> {
+    let responses = match (message, *player) {
+        (Message::RegisterTeam(register), _) => {
+            let result = match game.register_team(&register.name) {
+                Ok((expected_players, registration_token)) => {
+                    info!("Équipe {} inscrite.", register.name);
+                    RegisterTeamResult::Ok(RegisterTeamResultOk { expected_players, registration_token })
+                }
+                Err(e) => RegisterTeamResult::Err(e.as_str().to_string()),
+            };
+            vec![Message::RegisterTeamResult(result)]
+        }
+        (Message::SubscribePlayer(subscribe), None) => {
+            match game.subscribe_player(&subscribe.name, &subscribe.registration_token) {
+                Ok(id) => {
+                    info!("Joueur {} inscrit.", subscribe.name);
+                    *player = Some(id);
+                    let mut responses = vec![Message::SubscribePlayerResult(SubscribePlayerResult::Ok)];
+                    responses.extend(game.take_messages(id));
+                    responses.push(Message::RadarView(game.radar_view(id)));
+                    responses
+                }
+                Err(e) => vec![Message::SubscribePlayerResult(SubscribePlayerResult::Err(e.as_str().to_string()))],
+            }
+        }
+        (Message::SubscribePlayer(subscribe), Some(_)) => {
+            warn!("Inscription de {} refusée: la connexion a déjà un joueur", subscribe.name);
+            let reason = RegistrationError::AlreadyRegistered.as_str().to_string();
+            vec![Message::SubscribePlayerResult(SubscribePlayerResult::Err(reason))]
+        }
+        (Message::Action(action), Some(id)) => {
+            let result = match action {
+                Action::MoveTo(direction) => game.move_player(id, &direction),
+                Action::SolveChallenge { answer } => game.solve_challenge(id, &answer),
+            };
+            // Indices et challenges en attente passent avant la réponse à l'action.
+            let mut responses = game.take_messages(id);
+            responses.push(match result {
+                Ok(()) => Message::RadarView(game.radar_view(id)),
+                Err(e) => Message::ActionError(e),
+            });
+            responses
+        }
+        (other, _) => {
+            warn!("Message inattendu côté serveur, connexion fermée: {:?}", other);
+            return None;
+        }
+    };
+    Some(responses)
+}
+
+/// Lit la trame suivante et la désérialise, avec le même découpage que `FramedStream::recv`.
+async fn read_message<R: AsyncRead + Unpin>(reader: &mut R, max_frame_size: usize) -> Result<Message, TransportError> {
+    let mut header = [0u8; 4];
+    let mut filled = 0;
+    while filled < header.len() {
+        match reader.read(&mut header[filled..]).await? {
+            0 if filled == 0 => return Err(TransportError::Closed),
+            0 => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+            n => filled += n,
+        }
+    }
+    let mut body = vec![0u8; frame_size(header, max_frame_size)?];
+    reader.read_exact(&mut body).await?;
+    Ok(serde_json::from_slice(&body)?)
+}
+
+/// Envoie les messages de la file d'une connexion jusqu'à sa fermeture ou une erreur d'écriture.
+async fn write_messages(mut writer: OwnedWriteHalf, mut queue: mpsc::Receiver<Message>, config: Arc<ServerConfig>) {
+    while let Some(first) = queue.recv().await {
+        // Les messages déjà en file partent dans la même écriture.
+        let mut buffer = Vec::new();
+        let mut next = Some(first);
+        while let Some(message) = next {
+            let frame = serde_json::to_vec(&message)
+                .map_err(TransportError::from)
+                .and_then(|body| encode_frame(&body, config.max_frame_size));
+            match frame {
+                Ok(frame) => buffer.extend(frame),
+                Err(e) => warn!("Message non envoyé: {}", e),
+            }
+            next = queue.try_recv().ok();
+        }
+        match timeout(config.write_timeout, writer.write_all(&buffer)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                warn!("Erreur lors de l'envoi de la réponse: {}", e);
+                return;
+            }
+            Err(_) => {
+                warn!("Délai d'écriture dépassé, connexion fermée");
+                return;
+            }
+        }
+    }
+}
+
+/// Traite les messages d'une connexion. Une connexion sert soit à inscrire des équipes,
+/// soit à un unique joueur qui y envoie ensuite toutes ses actions.
+/// À l'arrêt du serveur, une connexion sans joueur est fermée ; celle d'un joueur reste servie
+/// jusqu'à ce que le client la ferme, pour que sa partie se termine.
+async fn handle_client(stream: TcpStream, game: SharedGame, config: Arc<ServerConfig>, mut shutdown: watch::Receiver<bool>) {
+    // Une réponse part en plusieurs trames : sans ceci, Nagle retarde les suivantes.
+    if let Err(e) = stream.set_nodelay(true) {
+        warn!("Impossible de désactiver Nagle: {}", e);
+    }
+    let (mut reader, writer) = stream.into_split();
+    let (outgoing, queue) = mpsc::channel(config.outgoing_queue.max(1));
+    let writer = tokio::spawn(write_messages(writer, queue, config.clone()));
+    let mut player: Option<PlayerId> = None;
+    'connection: loop {
+        let received = tokio::select! {
+            received = timeout(config.read_timeout, read_message(&mut reader, config.max_frame_size)) => received,
+            _ = outgoing.closed() => break,
+            _ = shutdown.wait_for(|&stop| stop), if player.is_none() => {
+                info!("Arrêt du serveur: connexion sans joueur fermée");
+                break;
+            }
+        };
+        let message = match received {
+            Ok(Ok(message)) => message,
+            Ok(Err(TransportError::Json(e))) => {
+                warn!("Message invalide, connexion fermée: {}", e);
+                break;
+            }
+            Ok(Err(TransportError::Closed)) => {
+                info!("Connexion fermée par le client");
+                break;
+            }
+            Ok(Err(e)) => {
+                warn!("Erreur lors de la lecture du message: {}", e);
+                break;
+            }
+            Err(_) => {
+                info!("Délai de lecture dépassé, connexion fermée");
+                break;
+            }
+        };
+        info!("Message reçu: {:?}", message);
+
+        let responses = {
+            let mut game = game.lock().expect("état de partie empoisonné");
+            dispatch(&mut game, message, &mut player)
+        };
+        let Some(responses) = responses else {
+            break;
+        };
+        for response in responses {
+            // File pleine : le client ne lit plus ses réponses, sa requête suivante attendra.
+            if !matches!(timeout(config.write_timeout, outgoing.send(response)).await, Ok(Ok(()))) {
+                warn!("Le client ne consomme pas ses réponses, connexion fermée");
+                break 'connection;
+            }
+        }
+    }
+    // Les réponses déjà en file partent avant la fermeture.
+    drop(outgoing);
+    let _ = writer.await;
+    if let Some(id) = player {
+        game.lock().expect("état de partie empoisonné").disconnect(id);
+    }
+}
+
+/// Accepte les connexions jusqu'à ce que `shutdown` se termine, puis attend la fin des connexions
+/// encore ouvertes (voir `handle_client`) avant de rendre la main.
+pub async fn serve(listener: TcpListener, game: SharedGame, config: ServerConfig, shutdown: impl Future<Output = ()>) {
+    let config = Arc::new(config);
+    let slots = Arc::new(Semaphore::new(config.max_connections));
+    let (stop, stopped) = watch::channel(false);
+    tokio::pin!(shutdown);
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    // Typiquement plus de descripteurs disponibles : on laisse le temps d'en libérer.
+                    warn!("Échec d'une connexion: {}", e);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+        let Ok(slot) = slots.clone().try_acquire_owned() else {
+            warn!("Connexion refusée: {} connexions déjà ouvertes", config.max_connections);
+            continue;
+        };
+        let (game, config, stopped) = (game.clone(), config.clone(), stopped.clone());
+        tokio::spawn(async move {
+            handle_client(stream, game, config, stopped).await;
+            drop(slot);
+        });
+    }
+    drop(listener);
+    info!(
+        "Arrêt demandé: plus de nouvelles connexions, {} connexion(s) encore ouverte(s)",
+        config.max_connections - slots.available_permits()
+    );
+    stop.send_replace(true);
+    // Chaque connexion tient une place : toutes les récupérer, c'est attendre la fin de la dernière.
+    let _ = slots.acquire_many(config.max_connections as u32).await;
+    info!("Toutes les connexions sont fermées");
+}
+
+/// Serveur lancé en arrière-plan par `spawn_with`.
+pub struct ServerHandle {
+    address: SocketAddr,
+    stop: Option<oneshot::Sender<()>>,
+    thread: thread::JoinHandle<()>,
+}
+
+impl ServerHandle {
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Demande l'arrêt du serveur, comme un SIGINT : les parties en cours continuent.
+    pub fn shutdown(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+    }
+
+    /// Attend la fin du serveur, c'est-à-dire la fermeture de sa dernière connexion après `shutdown`.
+    pub fn join(self) {
+        self.thread.join().expect("thread du serveur en panique");
+    }
+}
+
+/// Lance un serveur local dans un thread, avec son propre runtime. Sans appel à `shutdown`,
+/// abandonner le `ServerHandle` laisse le serveur tourner jusqu'à la fin du processus.
+pub fn spawn_with(address: &str, game: GameState, config: ServerConfig) -> io::Result<ServerHandle> {
+    let listener = std::net::TcpListener::bind(address)?;
+    let local = listener.local_addr()?;
+    listener.set_nonblocking(true)?;
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    let (stop, stopped) = oneshot::channel::<()>();
+    let thread = thread::spawn(move || {
+        runtime.block_on(async move {
+            let listener = TcpListener::from_std(listener).expect("socket d'écoute inutilisable");
+            let shutdown = async {
+                if stopped.await.is_err() {
+                    future::pending::<()>().await;
+                }
+            };
+            serve(listener, Arc::new(Mutex::new(game)), config, shutdown).await;
+        });
+    });
+    Ok(ServerHandle { address: local, stop: Some(stop), thread })
+}
+
+/// Lance un serveur local en arrière-plan (utile pour les tests) et renvoie son adresse.
+pub fn spawn(address: &str, game: GameState) -> io::Result<SocketAddr> {
+    spawn_with(address, game, ServerConfig::default()).map(|handle| handle.address())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenge::ChallengeRules;
+    use crate::game::GameConfig;
+    use crate::maze::Maze;
+    use shared::messages::{
+        secret_sum_modulo, ActionError, Challenge, Hint, RadarView, RegisterTeam, RelativeDirection, SubscribePlayer,
+    };
+    use shared::transport::FramedStream;
+    use std::net::TcpStream;
+    use std::time::Instant;
+
+    fn connect(address: SocketAddr) -> FramedStream<TcpStream> {
+        FramedStream::new(TcpStream::connect(address).unwrap())
+    }
+
+    fn register(address: SocketAddr, name: &str) -> String {
+        let mut admin = connect(address);
+        admin.send(&Message::RegisterTeam(RegisterTeam { name: name.into() })).unwrap();
+        match admin.recv().unwrap() {
+            Message::RegisterTeamResult(RegisterTeamResult::Ok(ok)) => ok.registration_token,
+            other => panic!("réponse inattendue: {:?}", other),
+        }
+    }
+
+    /// Lit les messages jusqu'à la réponse à la requête (RadarView ou ActionError) incluse.
+    fn recv_response(stream: &mut FramedStream<TcpStream>) -> Vec<Message> {
+        let mut messages = Vec::new();
+        loop {
+            let message = stream.recv().unwrap();
+            let done = matches!(message, Message::RadarView(_) | Message::ActionError(_));
+            messages.push(message);
+            if done {
+                return messages;
+            }
+        }
+    }
+
+    fn secret_of(messages: &[Message]) -> u64 {
+        messages.iter()
+            .find_map(|m| match m {
+                Message::Hint(Hint::Secret(secret)) => Some(*secret),
+                _ => None,
+            })
+            .expect("aucun secret reçu")
+    }
+
+    fn subscribe(address: SocketAddr, token: &str, name: &str) -> (FramedStream<TcpStream>, u64) {
+        let mut player = connect(address);
+        player.send(&Message::SubscribePlayer(SubscribePlayer { name: name.into(), registration_token: token.into() }))
+            .unwrap();
+        let messages = recv_response(&mut player);
+        assert_eq!(messages[0], Message::SubscribePlayerResult(SubscribePlayerResult::Ok));
+        (player, secret_of(&messages))
+    }
+
+    #[test]
+    fn test_register_subscribe_and_move() {
+        let maze = Maze::from_ascii(
+            "
+            +-+
+            | |
+            +-+
+            ",
+        );
+        let address = spawn("127.0.0.1:0", GameState::new(maze, GameConfig::default())).unwrap();
+
+        let token = register(address, "alpha");
+        let (mut player, _) = subscribe(address, &token, "bob");
+
+        player.send(&Message::Action(Action::MoveTo(RelativeDirection::Front))).unwrap();
+        assert_eq!(player.recv().unwrap(), Message::ActionError(ActionError::CannotPassThroughWall));
+        player.send(&Message::Action(Action::SolveChallenge { answer: "0".into() })).unwrap();
+        assert_eq!(player.recv().unwrap(), Message::ActionError(ActionError::NoRunningChallenge));
+    }
+
+    #[test]
+    fn test_secret_sum_challenge() {
+        let config = GameConfig {
+            players_per_team: 2,
+            challenges: ChallengeRules { secret_sum_every: Some(1), sos_every: None },
+            ..GameConfig::default()
+        };
+        let address = spawn("127.0.0.1:0", GameState::new(Maze::open(3, 3), config)).unwrap();
+        let token = register(address, "alpha");
+        let (mut alice, _) = subscribe(address, &token, "alice");
+        let (_bob, bob_secret) = subscribe(address, &token, "bob");
+
+        // Dans un labyrinthe ouvert, au moins une direction mène à une cellule voisine.
+        let messages = [RelativeDirection::Front, RelativeDirection::Right, RelativeDirection::Back]
+            .into_iter()
+            .map(|direction| {
+                alice.send(&Message::Action(Action::MoveTo(direction))).unwrap();
+                recv_response(&mut alice)
+            })
+            .find(|messages| matches!(messages.last(), Some(Message::RadarView(_))))
+            .unwrap();
+        let modulo = match &messages[..] {
+            [Message::Hint(Hint::Secret(_)), Message::Challenge(Challenge::SecretSumModulo(m)), Message::RadarView(_)] => *m,
+            other => panic!("réponse inattendue: {:?}", other),
+        };
+        let answer = secret_sum_modulo([secret_of(&messages), bob_secret], modulo);
+
+        alice.send(&Message::Action(Action::MoveTo(RelativeDirection::Back))).unwrap();
+        assert_eq!(alice.recv().unwrap(), Message::ActionError(ActionError::SolveChallengeFirst));
+        let wrong = ((answer + 1) % modulo).to_string();
+        alice.send(&Message::Action(Action::SolveChallenge { answer: wrong })).unwrap();
+        assert_eq!(alice.recv().unwrap(), Message::ActionError(ActionError::InvalidChallengeSolution));
+        alice.send(&Message::Action(Action::SolveChallenge { answer: answer.to_string() })).unwrap();
+        assert!(matches!(alice.recv().unwrap(), Message::RadarView(_)));
+        alice.send(&Message::Action(Action::SolveChallenge { answer: answer.to_string() })).unwrap();
+        assert_eq!(alice.recv().unwrap(), Message::ActionError(ActionError::NoRunningChallenge));
+    }
+
+    #[test]
+    fn test_max_connections() {
+        let config = ServerConfig { max_connections: 2, ..ServerConfig::default() };
+        let server = spawn_with("127.0.0.1:0", GameState::new(Maze::open(2, 2), GameConfig::default()), config).unwrap();
+        let address = server.address();
+        let mut admin = connect(address);
+        admin.send(&Message::RegisterTeam(RegisterTeam { name: "alpha".into() })).unwrap();
+        let token = match admin.recv().unwrap() {
+            Message::RegisterTeamResult(RegisterTeamResult::Ok(ok)) => ok.registration_token,
+            other => panic!("réponse inattendue: {:?}", other),
+        };
+        let (_player, _) = subscribe(address, &token, "alice");
+
+        let mut refused = connect(address);
+        refused.send(&Message::RegisterTeam(RegisterTeam { name: "beta".into() })).ok();
+        assert!(refused.recv().is_err());
+
+        // La place libérée par la connexion d'inscription est réutilisable.
+        drop(admin);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let mut retry = connect(address);
+            retry.send(&Message::RegisterTeam(RegisterTeam { name: "beta".into() })).ok();
+            if let Ok(message) = retry.recv() {
+                assert!(matches!(message, Message::RegisterTeamResult(RegisterTeamResult::Ok(_))));
+                break;
+            }
+            assert!(Instant::now() < deadline, "place jamais libérée");
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn test_read_timeout_closes_idle_connection() {
+        let config = ServerConfig { read_timeout: Duration::from_millis(100), ..ServerConfig::default() };
+        let server = spawn_with("127.0.0.1:0", GameState::new(Maze::open(2, 2), GameConfig::default()), config).unwrap();
+        let mut idle = connect(server.address());
+        let start = Instant::now();
+        assert!(matches!(idle.recv(), Err(TransportError::Closed)));
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_graceful_shutdown_lets_players_finish() {
+        let game = GameState::new(Maze::open(3, 3), GameConfig::default());
+        let mut server = spawn_with("127.0.0.1:0", game, ServerConfig::default()).unwrap();
+        let address = server.address();
+        let token = register(address, "alpha");
+        let mut admin = connect(address);
+        let (mut player, _) = subscribe(address, &token, "alice");
+
+        server.shutdown();
+        // La connexion d'inscription est fermée, celle du joueur reste servie.
+        assert!(matches!(admin.recv(), Err(TransportError::Closed)));
+        player.send(&Message::Action(Action::SolveChallenge { answer: "0".into() })).unwrap();
+        assert_eq!(player.recv().unwrap(), Message::ActionError(ActionError::NoRunningChallenge));
+        assert!(TcpStream::connect(address).is_err());
+
+        drop(player);
+        server.join();
+    }
+
+    /// Inscrit un joueur dans un labyrinthe fermé, où chaque déplacement est refusé.
+    fn subscribed_player() -> FramedStream<TcpStream> {
+        let maze = Maze::from_ascii(
+            "
+            +-+
+            | |
+            +-+
+            ",
+        );
+        let address = spawn("127.0.0.1:0", GameState::new(maze, GameConfig::default())).unwrap();
+        let token = register(address, "alpha");
+        subscribe(address, &token, "alice").0
+    }
+
+    #[test]
+    fn test_action_before_subscribe_closes_connection() {
+        let address = spawn("127.0.0.1:0", GameState::new(Maze::open(2, 2), GameConfig::default())).unwrap();
+        let mut stream = connect(address);
+        stream.send(&Message::Action(Action::MoveTo(RelativeDirection::Front))).unwrap();
+        assert!(matches!(stream.recv(), Err(TransportError::Closed)));
+    }
+
+    #[test]
+    fn test_second_subscribe_is_rejected() {
+        let mut player = subscribed_player();
+        player.send(&Message::SubscribePlayer(SubscribePlayer { name: "bob".into(), registration_token: "x".into() }))
+            .unwrap();
+        assert_eq!(
+            player.recv().unwrap(),
+            Message::SubscribePlayerResult(SubscribePlayerResult::Err("AlreadyRegistered".into()))
+        );
+        // Le joueur déjà inscrit garde sa connexion.
+        player.send(&Message::Action(Action::MoveTo(RelativeDirection::Front))).unwrap();
+        assert_eq!(player.recv().unwrap(), Message::ActionError(ActionError::CannotPassThroughWall));
+    }
+
+    #[test]
+    fn test_client_bound_message_closes_connection() {
+        let mut player = subscribed_player();
+        player.send(&Message::RadarView(RadarView("ieysGjGO8papd/a".into()))).unwrap();
+        assert!(matches!(player.recv(), Err(TransportError::Closed)));
+    }
+
+    #[test]
+    fn test_invalid_frame_closes_connection() {
+        let mut player = subscribed_player();
+        player.send_frame(b"{pas du json").unwrap();
+        assert!(matches!(player.recv(), Err(TransportError::Closed)));
+    }
+}
@@ -0,0 +1,222 @@
+use shared::messages::{RadarView, RelativeDirection};
+use shared::radar::{Passage, RadarGrid, RadarItem};
+
+use crate::maze::{Direction, Maze, Position};
+
+/// Éléments du jeu susceptibles d'apparaître dans une vue radar, en coordonnées absolues.
+/// Le joueur qui regarde ne doit pas figurer dans `allies`.
+#[derive(Debug, Default, Clone)]
+pub struct Entities {
+    pub allies: Vec<Position>,
+    pub enemies: Vec<Position>,
+    pub monsters: Vec<Position>,
+    pub hints: Vec<Position>,
+    pub exit: Option<Position>,
+}
+
+impl Entities {
+    /// Élément affiché sur une cellule. Une cellule n'affiche qu'un élément : les dangers passent en premier,
+    /// puis la sortie, qui reste visible quand des alliés déjà sortis s'y trouvent.
+    fn item_at(&self, pos: Position) -> RadarItem {
+        if self.monsters.contains(&pos) {
+            RadarItem::Monster
+        } else if self.enemies.contains(&pos) {
+            RadarItem::Enemy
+        } else if self.exit == Some(pos) {
+            RadarItem::Target
+        } else if self.allies.contains(&pos) {
+            RadarItem::Ally
+        } else if self.hints.contains(&pos) {
+            RadarItem::Hint
+        } else {
+            RadarItem::None
+        }
+    }
+}
+
+/// Cellule absolue (éventuellement hors du labyrinthe) de la case (`row`, `col`) de la vue,
+/// pour un joueur en `position` regardant vers `facing`.
+fn absolute_cell(position: Position, facing: Direction, row: usize, col: usize) -> (i64, i64) {
+    let front = 1 - row as i64;
+    let right = col as i64 - 1;
+    let (fr, fc) = facing.as_offset();
+    let (rr, rc) = facing.turn_right().as_offset();
+    (
+        position.0 as i64 + front * fr + right * rr,
+        position.1 as i64 + front * fc + right * rc,
+    )
+}
+
+fn inside(maze: &Maze, (r, c): (i64, i64)) -> Option<Position> {
+    let pos = (usize::try_from(r).ok()?, usize::try_from(c).ok()?);
+    maze.contains(pos).then_some(pos)
+}
+
+/// État de l'arête entre la case `a` et sa voisine `b`, située du côté `dir` de `a`.
+/// L'arête est connue dès qu'une des deux cellules est dans le labyrinthe.
+fn edge(maze: &Maze, a: (i64, i64), b: Option<(i64, i64)>, dir: Direction) -> Passage {
+    let (cell, side) = match (inside(maze, a), b.and_then(|b| inside(maze, b))) {
+        (Some(cell), _) => (cell, dir),
+        (None, Some(cell)) => (cell, dir.opposite()),
+        (None, None) => return Passage::Undefined,
+    };
+    if maze.is_wall(cell, side) {
+        Passage::Wall
+    } else {
+        Passage::Open
+    }
+}
+
+/// Calcule la vue radar 3x3 d'un joueur, tournée dans son repère : le haut de la vue est devant lui.
+/// Les passages qui ne bordent aucune cellule du labyrinthe sont `Undefined`.
+pub fn compute_radar(maze: &Maze, position: Position, facing: Direction, entities: &Entities) -> RadarGrid {
+    let cell = |row: usize, col: usize| absolute_cell(position, facing, row, col);
+    let mut grid = RadarGrid::default();
+    for row in 0..4 {
+        for col in 0..3 {
+            // Arête au-dessus de la case (row, col), ou sous la dernière ligne.
+            let passage = if row < 3 {
+                let above = (row > 0).then(|| cell(row - 1, col));
+                edge(maze, cell(row, col), above, facing.turn(&RelativeDirection::Front))
+            } else {
+                edge(maze, cell(2, col), None, facing.turn(&RelativeDirection::Back))
+            };
+            grid.set_horizontal(row, col, passage);
+        }
+    }
+    for row in 0..3 {
+        for col in 0..4 {
+            // Arête à gauche de la case (row, col), ou à droite de la dernière colonne.
+            let passage = if col < 3 {
+                let left = (col > 0).then(|| cell(row, col - 1));
+                edge(maze, cell(row, col), left, facing.turn(&RelativeDirection::Left))
+            } else {
+                edge(maze, cell(row, 2), None, facing.turn(&RelativeDirection::Right))
+            };
+            grid.set_vertical(row, col, passage);
+        }
+    }
+    for row in 0..3 {
+        for col in 0..3 {
+            if let Some(pos) = inside(maze, cell(row, col)) {
+                grid.set_item(row, col, entities.item_at(pos));
+            }
+        }
+    }
+    grid
+}
+
+/// Vue radar encodée, prête à être envoyée dans un message `RadarView`.
+pub fn radar_view(maze: &Maze, position: Position, facing: Direction, entities: &Entities) -> RadarView {
+    RadarView(compute_radar(maze, position, facing, entities).encode())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> Maze {
+        Maze::from_ascii(
+            "
+            +-+-+-+-+
+            |   |   |
+            + +-+ + +
+            |     |E|
+            +-+ +-+ +
+            |       |
+            +-+-+-+-+
+            ",
+        )
+    }
+
+    #[test]
+    fn test_walls_seen_facing_north() {
+        let grid = compute_radar(&layout(), (1, 1), Direction::North, &Entities::default());
+        assert_eq!(grid.player_passage(RelativeDirection::Front), Passage::Wall);
+        assert_eq!(grid.player_passage(RelativeDirection::Left), Passage::Open);
+        assert_eq!(grid.player_passage(RelativeDirection::Right), Passage::Open);
+        assert_eq!(grid.player_passage(RelativeDirection::Back), Passage::Open);
+        assert_eq!(grid.passage(0, 0, RelativeDirection::Back), Passage::Open);
+        assert_eq!(grid.horizontal(0, 0), Passage::Wall);
+    }
+
+    #[test]
+    fn test_rotation_is_consistent() {
+        // La même arête vue en regardant vers l'est : l'ancien « devant » est à gauche.
+        let north = compute_radar(&layout(), (1, 1), Direction::North, &Entities::default());
+        let east = compute_radar(&layout(), (1, 1), Direction::East, &Entities::default());
+        let south = compute_radar(&layout(), (1, 1), Direction::South, &Entities::default());
+        assert_eq!(east.player_passage(RelativeDirection::Left), north.player_passage(RelativeDirection::Front));
+        assert_eq!(east.player_passage(RelativeDirection::Front), north.player_passage(RelativeDirection::Right));
+        assert_eq!(south.player_passage(RelativeDirection::Back), north.player_passage(RelativeDirection::Front));
+        // La case (i, j) de la vue vers l'est est la case (j, 2 - i) de la vue vers le nord.
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_eq!(east.vertical(i, j), north.horizontal(j, 2 - i), "({}, {})", i, j);
+                assert_eq!(east.item(i, j), north.item(j, 2 - i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_outside_is_undefined() {
+        let grid = compute_radar(&layout(), (0, 0), Direction::North, &Entities::default());
+        // Ligne du haut et colonne de gauche hors du labyrinthe.
+        for col in 0..3 {
+            assert_eq!(grid.horizontal(0, col), Passage::Undefined);
+        }
+        for row in 0..3 {
+            assert_eq!(grid.vertical(row, 0), Passage::Undefined);
+        }
+        // Le bord du labyrinthe, lui, est un mur.
+        assert_eq!(grid.horizontal(1, 1), Passage::Wall);
+        assert_eq!(grid.vertical(1, 1), Passage::Wall);
+        assert_eq!(grid.horizontal(1, 0), Passage::Undefined);
+    }
+
+    #[test]
+    fn test_items_priority_and_rotation() {
+        let entities = Entities {
+            allies: vec![(1, 2)],
+            enemies: vec![(0, 1)],
+            monsters: vec![(2, 1), (0, 1)],
+            hints: vec![(1, 0)],
+            exit: Some((1, 3)),
+        };
+        let grid = compute_radar(&layout(), (1, 1), Direction::West, &entities);
+        assert_eq!(grid.item(0, 1), RadarItem::Hint);
+        assert_eq!(grid.item(1, 0), RadarItem::Monster);
+        assert_eq!(grid.item(1, 2), RadarItem::Monster);
+        assert_eq!(grid.item(2, 1), RadarItem::Ally);
+        assert_eq!(grid.item(1, 1), RadarItem::None);
+    }
+
+    #[test]
+    fn test_exit_shown_over_allies() {
+        // Un allié déjà sorti reste sur la sortie : elle doit rester visible pour les autres.
+        let entities = Entities { allies: vec![(1, 3)], exit: Some((1, 3)), ..Entities::default() };
+        let grid = compute_radar(&layout(), (1, 2), Direction::North, &entities);
+        assert_eq!(grid.item(1, 2), RadarItem::Target);
+    }
+
+    #[test]
+    fn test_golden_views() {
+        let maze = layout();
+        let entities = Entities {
+            allies: vec![(2, 1)],
+            enemies: vec![],
+            monsters: vec![],
+            hints: vec![(0, 0)],
+            exit: Some(maze.exit()),
+        };
+        let cases = [
+            ((1, 1), Direction::North, "QzMQMzAvqaaaaqa"),
+            ((1, 2), Direction::East, "QzLvLPQMcaaaaba"),
+            ((0, 0), Direction::South, "yKOawjGaaabaaaa"),
+            ((2, 3), Direction::West, "fgkaksuaaaaiaaa"),
+        ];
+        for (position, facing, expected) in cases {
+            assert_eq!(radar_view(&maze, position, facing, &entities).0, expected, "{:?} {:?}", position, facing);
+        }
+    }
+}
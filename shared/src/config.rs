@@ -0,0 +1,214 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// Hôte utilisé quand rien d'autre n'est configuré.
+pub const DEFAULT_HOST: &str = "127.0.0.1";
+/// Port utilisé quand rien d'autre n'est configuré.
+pub const DEFAULT_PORT: u16 = 8778;
+
+/// Variable d'environnement donnant l'hôte du serveur.
+pub const HOST_VAR: &str = "SQP_HOST";
+/// Variable d'environnement donnant le port du serveur.
+pub const PORT_VAR: &str = "SQP_PORT";
+/// Variable d'environnement donnant le chemin du fichier de configuration TOML.
+pub const CONFIG_VAR: &str = "SQP_CONFIG";
+
+/// Options de ligne de commande reconnues par `ServerAddress::resolve`, pour les messages d'aide.
+pub const USAGE: &str = "[--config FICHIER.toml] [--host HÔTE] [--port PORT] [--address HÔTE:PORT]";
+
+/// Erreurs de lecture de la configuration.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Une option de ligne de commande attend une valeur.
+    MissingValue(String),
+    /// Le port n'est pas un entier entre 0 et 65535 ; `origin` indique d'où vient la valeur.
+    InvalidPort { origin: String, value: String },
+    /// `--address` attend la forme `HÔTE:PORT`.
+    InvalidAddress(String),
+    /// Le fichier de configuration n'a pas pu être lu.
+    Read { path: PathBuf, source: io::Error },
+    /// Le fichier de configuration n'est pas un TOML valide ou contient une clé inconnue.
+    Parse { path: PathBuf, source: toml::de::Error },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::MissingValue(flag) => write!(f, "valeur manquante pour {}", flag),
+            ConfigError::InvalidPort { origin, value } => write!(f, "{}: port invalide: {}", origin, value),
+            ConfigError::InvalidAddress(value) => write!(f, "adresse invalide (attendu HÔTE:PORT): {}", value),
+            ConfigError::Read { path, source } => write!(f, "lecture de {} impossible: {}", path.display(), source),
+            ConfigError::Parse { path, source } => write!(f, "{} invalide: {}", path.display(), source),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Read { source, .. } => Some(source),
+            ConfigError::Parse { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Contenu du fichier de configuration ; une clé absente garde la valeur par défaut.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileConfig {
+    host: Option<String>,
+    port: Option<u16>,
+}
+
+/// Adresse du serveur de jeu, sur laquelle le serveur écoute et à laquelle les clients se connectent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerAddress {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Default for ServerAddress {
+    fn default() -> Self {
+        ServerAddress { host: DEFAULT_HOST.to_string(), port: DEFAULT_PORT }
+    }
+}
+
+impl fmt::Display for ServerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.host, self.port)
+    }
+}
+
+impl ServerAddress {
+    /// Résout l'adresse depuis les arguments du programme et les variables d'environnement du processus.
+    pub fn from_env_and_args(args: impl IntoIterator<Item = String>) -> Result<(Self, Vec<String>), ConfigError> {
+        Self::resolve(args, |name| std::env::var(name).ok())
+    }
+
+    /// Résout l'adresse par couches, chacune remplaçant la précédente : valeurs par défaut,
+    /// fichier TOML (`--config` ou `SQP_CONFIG`), variables `SQP_HOST` et `SQP_PORT`,
+    /// puis options `--host`, `--port` et `--address`.
+    /// Les arguments non reconnus sont rendus, dans l'ordre, pour les options propres au binaire.
+    pub fn resolve(
+        args: impl IntoIterator<Item = String>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<(Self, Vec<String>), ConfigError> {
+        let mut config_path = None;
+        let mut host = None;
+        let mut port = None;
+        let mut rest = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or_else(|| ConfigError::MissingValue(flag.clone()));
+            match flag.as_str() {
+                "--config" => config_path = Some(PathBuf::from(value()?)),
+                "--host" => host = Some(value()?),
+                "--port" => port = Some(parse_port("--port", &value()?)?),
+                "--address" => {
+                    let address = value()?;
+                    let (h, p) = address.rsplit_once(':').ok_or_else(|| ConfigError::InvalidAddress(address.clone()))?;
+                    if h.is_empty() {
+                        return Err(ConfigError::InvalidAddress(address.clone()));
+                    }
+                    host = Some(h.to_string());
+                    port = Some(parse_port("--address", p)?);
+                }
+                _ => rest.push(flag),
+            }
+        }
+
+        let mut address = ServerAddress::default();
+        if let Some(path) = config_path.or_else(|| env(CONFIG_VAR).map(PathBuf::from)) {
+            let file = load_file(&path)?;
+            address.host = file.host.unwrap_or(address.host);
+            address.port = file.port.unwrap_or(address.port);
+        }
+        if let Some(h) = env(HOST_VAR) {
+            address.host = h;
+        }
+        if let Some(p) = env(PORT_VAR) {
+            address.port = parse_port(PORT_VAR, &p)?;
+        }
+        address.host = host.unwrap_or(address.host);
+        address.port = port.unwrap_or(address.port);
+        Ok((address, rest))
+    }
+}
+
+fn parse_port(origin: &str, value: &str) -> Result<u16, ConfigError> {
+    value.trim().parse().map_err(|_| ConfigError::InvalidPort { origin: origin.to_string(), value: value.to_string() })
+}
+
+fn load_file(path: &Path) -> Result<FileConfig, ConfigError> {
+    let text = fs::read_to_string(path).map_err(|source| ConfigError::Read { path: path.to_path_buf(), source })?;
+    toml::from_str(&text).map_err(|source| ConfigError::Parse { path: path.to_path_buf(), source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn resolve(list: &[&str], vars: &[(&str, &str)]) -> Result<(ServerAddress, Vec<String>), ConfigError> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        ServerAddress::resolve(args(list), |name| vars.get(name).cloned())
+    }
+
+    /// Écrit un fichier de configuration propre au test dans le répertoire temporaire.
+    fn config_file(name: &str, content: &str) -> String {
+        let path = std::env::temp_dir().join(format!("sqp-config-{}-{}.toml", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_defaults_and_unknown_args() {
+        let (address, rest) = resolve(&["--team", "alpha", "--coordinated"], &[]).unwrap();
+        assert_eq!(address, ServerAddress::default());
+        assert_eq!(address.to_string(), "127.0.0.1:8778");
+        assert_eq!(rest, args(&["--team", "alpha", "--coordinated"]));
+    }
+
+    #[test]
+    fn test_layers_override_in_order() {
+        let path = config_file("layers", "host = \"10.0.0.1\"\nport = 9000\n");
+        let (address, _) = resolve(&["--config", &path], &[]).unwrap();
+        assert_eq!(address, ServerAddress { host: "10.0.0.1".into(), port: 9000 });
+
+        let vars = [(CONFIG_VAR, path.as_str()), (PORT_VAR, "9001")];
+        let (address, _) = resolve(&[], &vars).unwrap();
+        assert_eq!(address, ServerAddress { host: "10.0.0.1".into(), port: 9001 });
+
+        let (address, _) = resolve(&["--port", "9002"], &vars).unwrap();
+        assert_eq!(address, ServerAddress { host: "10.0.0.1".into(), port: 9002 });
+
+        let (address, _) = resolve(&["--address", "localhost:9003", "--host", "0.0.0.0"], &vars).unwrap();
+        assert_eq!(address, ServerAddress { host: "0.0.0.0".into(), port: 9003 });
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(matches!(resolve(&["--port"], &[]), Err(ConfigError::MissingValue(flag)) if flag == "--port"));
+        assert!(matches!(resolve(&["--port", "70000"], &[]), Err(ConfigError::InvalidPort { .. })));
+        assert!(matches!(resolve(&[], &[(PORT_VAR, "abc")]), Err(ConfigError::InvalidPort { origin, .. }) if origin == PORT_VAR));
+        assert!(matches!(resolve(&["--address", "localhost"], &[]), Err(ConfigError::InvalidAddress(_))));
+        assert!(matches!(resolve(&["--address", ":8778"], &[]), Err(ConfigError::InvalidAddress(_))));
+
+        let missing = std::env::temp_dir().join("sqp-config-absent.toml");
+        assert!(matches!(
+            resolve(&["--config", &missing.to_string_lossy()], &[]),
+            Err(ConfigError::Read { .. })
+        ));
+        let typo = config_file("typo", "hots = \"10.0.0.1\"\n");
+        assert!(matches!(resolve(&["--config", &typo], &[]), Err(ConfigError::Parse { .. })));
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::BTreeSet;
+use std::io::Cursor;
+
+use crate::messages::*;
+use crate::transport::{FramedStream, TransportError};
+
+/// Messages envoyés par le client, avec leur JSON canonique.
+fn client_fixtures() -> Vec<(Message, &'static str)> {
+    let mut fixtures = vec![
+        (
+            Message::RegisterTeam(RegisterTeam { name: "curious_broccoli".into() }),
+            r#"{"RegisterTeam":{"name":"curious_broccoli"}}"#,
+        ),
+        (
+            Message::SubscribePlayer(SubscribePlayer { name: "alice".into(), registration_token: "SECRET".into() }),
+            r#"{"SubscribePlayer":{"name":"alice","registration_token":"SECRET"}}"#,
+        ),
+        (
+            Message::Action(Action::SolveChallenge { answer: "42".into() }),
+            r#"{"Action":{"SolveChallenge":{"answer":"42"}}}"#,
+        ),
+    ];
+    for (direction, json) in [
+        (RelativeDirection::Front, r#"{"Action":{"MoveTo":"Front"}}"#),
+        (RelativeDirection::Right, r#"{"Action":{"MoveTo":"Right"}}"#),
+        (RelativeDirection::Back, r#"{"Action":{"MoveTo":"Back"}}"#),
+        (RelativeDirection::Left, r#"{"Action":{"MoveTo":"Left"}}"#),
+    ] {
+        fixtures.push((Message::Action(Action::MoveTo(direction)), json));
+    }
+    fixtures
+}
+
+/// Messages envoyés par le serveur, avec leur JSON canonique.
+fn server_fixtures() -> Vec<(Message, &'static str)> {
+    let mut fixtures = vec![
+        (
+            Message::RegisterTeamResult(RegisterTeamResult::Ok(RegisterTeamResultOk {
+                expected_players: 3,
+                registration_token: "SECRET".into(),
+            })),
+            r#"{"RegisterTeamResult":{"Ok":{"expected_players":3,"registration_token":"SECRET"}}}"#,
+        ),
+        (
+            Message::RegisterTeamResult(RegisterTeamResult::Err("AlreadyRegistered".into())),
+            r#"{"RegisterTeamResult":{"Err":"AlreadyRegistered"}}"#,
+        ),
+        (Message::SubscribePlayerResult(SubscribePlayerResult::Ok), r#"{"SubscribePlayerResult":"Ok"}"#),
+        (
+            Message::SubscribePlayerResult(SubscribePlayerResult::Err("InvalidRegistrationToken".into())),
+            r#"{"SubscribePlayerResult":{"Err":"InvalidRegistrationToken"}}"#,
+        ),
+        (Message::RadarView(RadarView("ieysGjGO8papd/a".into())), r#"{"RadarView":"ieysGjGO8papd/a"}"#),
+        (
+            Message::Hint(Hint::RelativeCompass { angle: 12.5 }),
+            r#"{"Hint":{"RelativeCompass":{"angle":12.5}}}"#,
+        ),
+        (
+            Message::Hint(Hint::GridSize { columns: 10, rows: 8 }),
+            r#"{"Hint":{"GridSize":{"columns":10,"rows":8}}}"#,
+        ),
+        (Message::Hint(Hint::Secret(42)), r#"{"Hint":{"Secret":42}}"#),
+        (Message::Hint(Hint::SOSHelper), r#"{"Hint":"SOSHelper"}"#),
+        (Message::Challenge(Challenge::SecretSumModulo(17)), r#"{"Challenge":{"SecretSumModulo":17}}"#),
+        (Message::Challenge(Challenge::SOS), r#"{"Challenge":"SOS"}"#),
+    ];
+    for (error, json) in [
+        (ActionError::CannotPassThroughWall, r#"{"ActionError":"CannotPassThroughWall"}"#),
+        (ActionError::CannotPassThroughOpponent, r#"{"ActionError":"CannotPassThroughOpponent"}"#),
+        (ActionError::NoRunningChallenge, r#"{"ActionError":"NoRunningChallenge"}"#),
+        (ActionError::SolveChallengeFirst, r#"{"ActionError":"SolveChallengeFirst"}"#),
+        (ActionError::InvalidChallengeSolution, r#"{"ActionError":"InvalidChallengeSolution"}"#),
+    ] {
+        fixtures.push((Message::ActionError(error), json));
+    }
+    fixtures
+}
+
+fn all_fixtures() -> Vec<(Message, &'static str)> {
+    let mut fixtures = client_fixtures();
+    fixtures.extend(server_fixtures());
+    fixtures
+}
+
+#[test]
+fn test_serialize_to_fixtures() {
+    for (message, json) in all_fixtures() {
+        assert_eq!(serde_json::to_string(&message).unwrap(), json);
+    }
+}
+
+#[test]
+fn test_deserialize_fixtures() {
+    for (message, json) in all_fixtures() {
+        assert_eq!(serde_json::from_str::<Message>(json).unwrap(), message, "{}", json);
+    }
+}
+
+#[test]
+fn test_fixtures_cover_every_variant() {
+    // Un `match` exhaustif, une branche par variante jusqu'aux directions et aux erreurs d'action :
+    // une nouvelle variante casse la compilation tant qu'elle n'a pas sa branche, et sa fixture.
+    let variant = |message: &Message| match message {
+        Message::RegisterTeam(_) => "RegisterTeam",
+        Message::RegisterTeamResult(RegisterTeamResult::Ok(_)) => "RegisterTeamResult::Ok",
+        Message::RegisterTeamResult(RegisterTeamResult::Err(_)) => "RegisterTeamResult::Err",
+        Message::SubscribePlayer(_) => "SubscribePlayer",
+        Message::SubscribePlayerResult(SubscribePlayerResult::Ok) => "SubscribePlayerResult::Ok",
+        Message::SubscribePlayerResult(SubscribePlayerResult::Err(_)) => "SubscribePlayerResult::Err",
+        Message::RadarView(_) => "RadarView",
+        Message::Hint(Hint::RelativeCompass { .. }) => "Hint::RelativeCompass",
+        Message::Hint(Hint::GridSize { .. }) => "Hint::GridSize",
+        Message::Hint(Hint::Secret(_)) => "Hint::Secret",
+        Message::Hint(Hint::SOSHelper) => "Hint::SOSHelper",
+        Message::Action(Action::MoveTo(RelativeDirection::Front)) => "Action::MoveTo(Front)",
+        Message::Action(Action::MoveTo(RelativeDirection::Right)) => "Action::MoveTo(Right)",
+        Message::Action(Action::MoveTo(RelativeDirection::Back)) => "Action::MoveTo(Back)",
+        Message::Action(Action::MoveTo(RelativeDirection::Left)) => "Action::MoveTo(Left)",
+        Message::Action(Action::SolveChallenge { .. }) => "Action::SolveChallenge",
+        Message::ActionError(ActionError::CannotPassThroughWall) => "ActionError::CannotPassThroughWall",
+        Message::ActionError(ActionError::CannotPassThroughOpponent) => "ActionError::CannotPassThroughOpponent",
+        Message::ActionError(ActionError::NoRunningChallenge) => "ActionError::NoRunningChallenge",
+        Message::ActionError(ActionError::SolveChallengeFirst) => "ActionError::SolveChallengeFirst",
+        Message::ActionError(ActionError::InvalidChallengeSolution) => "ActionError::InvalidChallengeSolution",
+        Message::Challenge(Challenge::SecretSumModulo(_)) => "Challenge::SecretSumModulo",
+        Message::Challenge(Challenge::SOS) => "Challenge::SOS",
+    };
+    let covered: BTreeSet<&str> = all_fixtures().iter().map(|(message, _)| variant(message)).collect();
+    assert_eq!(covered.len(), 23);
+}
+
+#[test]
+fn test_accepts_other_layouts() {
+    // Ordre des clés et espaces libres : seule la structure compte.
+    let json = "{ \"SubscribePlayer\" : {\n  \"registration_token\": \"SECRET\",\n  \"name\": \"alice\"\n} }";
+    assert_eq!(
+        serde_json::from_str::<Message>(json).unwrap(),
+        Message::SubscribePlayer(SubscribePlayer { name: "alice".into(), registration_token: "SECRET".into() })
+    );
+    let json = r#"{"Hint":{"RelativeCompass":{"angle":-90}}}"#;
+    assert_eq!(serde_json::from_str::<Message>(json).unwrap(), Message::Hint(Hint::RelativeCompass { angle: -90.0 }));
+}
+
+#[test]
+fn test_rejects_untagged_shapes() {
+    // Formes produites par les anciennes enums « untagged », ou invalides pour le protocole.
+    for json in [
+        r#"{"SubscribePlayerResult":null}"#,
+        r#"{"SubscribePlayerResult":"InvalidRegistrationToken"}"#,
+        r#"{"RegisterTeamResult":{"expected_players":3,"registration_token":"SECRET"}}"#,
+        r#"{"Hint":42}"#,
+        r#"{"Hint":null}"#,
+        r#"{"Hint":{"angle":12.5}}"#,
+        r#"{"Action":"Front"}"#,
+        r#"{"Action":{"answer":"42"}}"#,
+        r#"{"Action":{"MoveTo":"Up"}}"#,
+        r#"{"Challenge":17}"#,
+        r#"{"Challenge":null}"#,
+        r#"{"ActionError":null}"#,
+        r#"{"Unknown":{}}"#,
+    ] {
+        assert!(serde_json::from_str::<Message>(json).is_err(), "accepté à tort: {}", json);
+    }
+}
+
+#[test]
+fn test_wire_frames() {
+    for (message, json) in all_fixtures() {
+        let mut frame = (json.len() as u32).to_le_bytes().to_vec();
+        frame.extend(json.as_bytes());
+
+        let mut sender = FramedStream::new(Cursor::new(Vec::new()));
+        sender.send(&message).unwrap();
+        assert_eq!(sender.into_inner().into_inner(), frame, "{}", json);
+
+        let mut receiver = FramedStream::new(Cursor::new(frame));
+        assert_eq!(receiver.recv().unwrap(), message);
+        assert!(matches!(receiver.recv(), Err(TransportError::Closed)));
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt;
+
+/// Alphabet du protocole (minuscules d'abord), dans l'ordre des valeurs 0 à 63.
+/// Il n'y a pas de caractère de padding : le dernier groupe de 2 ou 3 caractères porte 1 ou 2 octets,
+/// et ses bits inutilisés doivent être nuls.
+pub const ALPHABET: &[u8; 64] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789+/";
+
+/// Marque des octets hors de l'alphabet dans `DECODE_TABLE`.
+const INVALID: u8 = 0xFF;
+
+/// Valeur de chaque octet dans l'alphabet, ou `INVALID`.
+const DECODE_TABLE: [u8; 256] = {
+    let mut table = [INVALID; 256];
+    let mut i = 0;
+    while i < ALPHABET.len() {
+        table[ALPHABET[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+};
+
+/// Erreurs de décodage base64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Une longueur de la forme 4n+1 ne peut porter aucun octet entier.
+    InvalidLength(usize),
+    /// Octet hors de l'alphabet, à la position `index` de l'entrée.
+    InvalidCharacter { index: usize, byte: u8 },
+    /// Les bits inutilisés du dernier caractère ne sont pas nuls : l'encodage n'est pas canonique.
+    NonZeroPadding,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength(len) => write!(f, "longueur invalide: {} (4n+1 impossible)", len),
+            DecodeError::InvalidCharacter { index, byte } => {
+                write!(f, "caractère non autorisé {:?} à la position {}", char::from(*byte), index)
+            }
+            DecodeError::NonZeroPadding => write!(f, "bits de remplissage non nuls"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Longueur de l'encodage de `len` octets : 4 caractères par groupe de 3, puis 2 ou 3 pour le reste.
+pub fn encoded_len(len: usize) -> usize {
+    len / 3 * 4 + [0, 2, 3][len % 3]
+}
+
+/// Encode des données binaires avec l'alphabet du protocole.
+pub fn encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(encoded_len(data.len()));
+    for chunk in data.chunks(3) {
+        let b1 = chunk.get(1).copied().unwrap_or(0);
+        let b2 = chunk.get(2).copied().unwrap_or(0);
+        let triple = (u32::from(chunk[0]) << 16) | (u32::from(b1) << 8) | u32::from(b2);
+        for i in 0..=chunk.len() {
+            encoded.push(char::from(ALPHABET[(triple >> (18 - 6 * i)) as usize & 0x3F]));
+        }
+    }
+    encoded
+}
+
+/// Décode une chaîne encodée par `encode`, en refusant toute entrée non canonique.
+pub fn decode(input: &str) -> Result<Vec<u8>, DecodeError> {
+    let input = input.as_bytes();
+    if input.len() % 4 == 1 {
+        return Err(DecodeError::InvalidLength(input.len()));
+    }
+    let mut output = Vec::with_capacity(input.len() / 4 * 3 + 2);
+    let mut buffer = 0u32;
+    let mut bits_filled = 0;
+    for (index, &byte) in input.iter().enumerate() {
+        let value = DECODE_TABLE[byte as usize];
+        if value == INVALID {
+            return Err(DecodeError::InvalidCharacter { index, byte });
+        }
+        buffer = (buffer << 6) | u32::from(value);
+        bits_filled += 6;
+        if bits_filled >= 8 {
+            bits_filled -= 8;
+            output.push((buffer >> bits_filled) as u8);
+            buffer &= (1 << bits_filled) - 1;
+        }
+    }
+    if buffer != 0 {
+        return Err(DecodeError::NonZeroPadding);
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_reference_vectors() {
+        let vectors: [(&[u8], &str); 10] = [
+            (b"", ""),
+            (&[0], "aa"),
+            (&[25], "gq"),
+            (&[26], "gG"),
+            (&[51], "mW"),
+            (&[52], "na"),
+            (&[61], "pq"),
+            (&[62], "pG"),
+            (&[63], "pW"),
+            (b"Hello, World!", "sgvSBg8SifDVCMXKiq"),
+        ];
+        for (data, text) in vectors {
+            assert_eq!(encode(data), text);
+            assert_eq!(decode(text).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_decode_table_matches_alphabet() {
+        for byte in 0..=255u8 {
+            match ALPHABET.iter().position(|&c| c == byte) {
+                Some(value) => assert_eq!(DECODE_TABLE[byte as usize] as usize, value),
+                None => {
+                    assert_eq!(DECODE_TABLE[byte as usize], INVALID);
+                    if byte.is_ascii() {
+                        let text = String::from_utf8(vec![b'a', b'a', byte, b'a']).unwrap();
+                        assert_eq!(decode(&text), Err(DecodeError::InvalidCharacter { index: 2, byte }));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_one_and_two_byte_inputs_round_trip() {
+        for b0 in 0..=255u8 {
+            assert_eq!(decode(&encode(&[b0])).unwrap(), [b0]);
+            for b1 in 0..=255u8 {
+                let encoded = encode(&[b0, b1]);
+                assert_eq!(encoded.len(), 3);
+                assert_eq!(decode(&encoded).unwrap(), [b0, b1]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_short_string_is_canonical_or_rejected() {
+        // Toute chaîne de 2 ou 3 caractères valides se décode exactement quand elle se réencode à l'identique.
+        for &c0 in ALPHABET {
+            for &c1 in ALPHABET {
+                let text = String::from_utf8(vec![c0, c1]).unwrap();
+                match decode(&text) {
+                    Ok(bytes) => assert_eq!(encode(&bytes), text),
+                    Err(e) => assert_eq!(e, DecodeError::NonZeroPadding, "{}", text),
+                }
+                for &c2 in ALPHABET {
+                    let text = String::from_utf8(vec![c0, c1, c2]).unwrap();
+                    match decode(&text) {
+                        Ok(bytes) => assert_eq!(encode(&bytes), text),
+                        Err(e) => assert_eq!(e, DecodeError::NonZeroPadding, "{}", text),
+                    }
+                }
+            }
+        }
+        // Un seul caractère final sur 16 (2 caractères) et sur 4 (3 caractères) est canonique.
+        let canonical = |len: usize| ALPHABET.iter().filter(|&&c| {
+            let mut text = vec![b'a'; len];
+            text[len - 1] = c;
+            decode(std::str::from_utf8(&text).unwrap()).is_ok()
+        }).count();
+        assert_eq!(canonical(2), 4);
+        assert_eq!(canonical(3), 16);
+    }
+
+    #[test]
+    fn test_errors() {
+        for len in [1, 5, 9, 13] {
+            assert_eq!(decode(&"a".repeat(len)), Err(DecodeError::InvalidLength(len)));
+        }
+        assert_eq!(decode("ab=c"), Err(DecodeError::InvalidCharacter { index: 2, byte: b'=' }));
+        assert_eq!(decode("ab c"), Err(DecodeError::InvalidCharacter { index: 2, byte: b' ' }));
+        assert_eq!(decode("aé"), Err(DecodeError::InvalidCharacter { index: 1, byte: 0xC3 }));
+        assert_eq!(decode("ab"), Err(DecodeError::NonZeroPadding));
+        assert_eq!(decode("aab"), Err(DecodeError::NonZeroPadding));
+        assert_eq!(decode("aaaaab"), Err(DecodeError::NonZeroPadding));
+        assert_eq!(decode("aaaaaaab"), Ok(vec![0, 0, 0, 0, 0, 1]));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_round_trip(data in proptest::collection::vec(any::<u8>(), 0..256)) {
+            let encoded = encode(&data);
+            prop_assert_eq!(encoded.len(), encoded_len(data.len()));
+            prop_assert!(encoded.bytes().all(|c| ALPHABET.contains(&c)));
+            prop_assert_eq!(decode(&encoded).unwrap(), data);
+        }
+
+        #[test]
+        fn prop_decode_never_panics(text in "[a-zA-Z0-9+/=é ]{0,64}") {
+            if let Ok(bytes) = decode(&text) {
+                prop_assert_eq!(encode(&bytes), text);
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+pub mod b64;
+
+use crate::maze::{Maze, MazeError};
+
+/// Encode une carte de labyrinthe en concaténant :
+/// - nx (u16 little-endian)
+/// - ny (u16 little-endian)
+/// - Le vecteur d'octets des murs horizontaux ((ny + 1) × nx passages × 2 bits)
+/// - Le vecteur d'octets des murs verticaux (ny × (nx + 1) passages × 2 bits)
+///
+/// Les tailles des vecteurs et les valeurs des passages sont vérifiées, comme au décodage.
+pub fn encode_labyrinth(nx: u16, ny: u16, horizontal: &[u8], vertical: &[u8]) -> Result<String, MazeError> {
+    Ok(Maze::from_packed(nx, ny, horizontal, vertical)?.encode())
+}
+
+/// Encode la vue radar en concaténant :
+/// - 3 octets pour les passages horizontaux (12 passages × 2 bits)
+/// - 3 octets pour les passages verticaux (12 passages × 2 bits)
+/// - 5 octets pour les items des 9 cellules (9 cellules × 4 bits, avec padding sur les 4 bits de poids faible)
+pub fn encode_radar(horiz: &[u8; 3], vert: &[u8; 3], cells: &[u8; 5]) -> String {
+    let mut data = Vec::new();
+    data.extend_from_slice(horiz);
+    data.extend_from_slice(vert);
+    data.extend_from_slice(cells);
+    b64::encode(&data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_encode_labyrinth() {
+        // 1 × 1 : deux murs horizontaux, puis un passage ouvert à l'ouest et un mur à l'est.
+        let encoded = encode_labyrinth(1, 1, &[0b1010_0000], &[0b0110_0000]).unwrap();
+        assert_eq!(b64::decode(&encoded).unwrap(), vec![1, 0, 1, 0, 0b1010_0000, 0b0110_0000]);
+
+        let horizontal = vec![0b11111111, 0b11000000];
+        let vertical = vec![0b10101010, 0b01010101];
+        assert_eq!(
+            encode_labyrinth(10, 10, &horizontal, &vertical),
+            Err(MazeError::InvalidLength { expected: 28, actual: 2 })
+        );
+        assert_eq!(encode_labyrinth(1, 1, &[0b1011_0000], &[0]), Err(MazeError::InvalidPassage(1)));
+        assert_eq!(encode_labyrinth(1, 1, &[0b1010_0001], &[0]), Err(MazeError::NonZeroPadding));
+    }
+
+    #[test]
+    fn test_encode_radar() {
+        let horiz = [0x12, 0x34, 0x56];
+        let vert = [0x78, 0x9A, 0xBC];
+        let cells = [0xDE, 0xF0, 0x12, 0x34, 0x56];
+        let encoded = encode_radar(&horiz, &vert, &cells);
+        assert!(!encoded.is_empty());
+    }
+}
@@ -1,2 +1,10 @@
 pub mod messages;
 pub mod encodings;
+pub mod transport;
+pub mod radar;
+pub mod maze;
+pub mod config;
+
+/// Conformité au protocole : chaque message, dans chaque sens, face à sa forme JSON publiée.
+#[cfg(test)]
+mod conformance;
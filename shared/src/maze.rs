@@ -0,0 +1,437 @@
+use std::fmt;
+
+use crate::encodings::b64;
+use crate::messages::RelativeDirection;
+use crate::radar::{decode_passages, encode_passages, Passage};
+
+/// Position dans le labyrinthe : (ligne, colonne).
+pub type Position = (usize, usize);
+
+/// Direction absolue dans le labyrinthe (le nord est en haut).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::North => Direction::West,
+            Direction::West => Direction::South,
+            Direction::South => Direction::East,
+            Direction::East => Direction::North,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        self.turn_right().turn_right()
+    }
+
+    /// Direction absolue obtenue en se tournant vers `relative` depuis `self`.
+    pub fn turn(self, relative: &RelativeDirection) -> Self {
+        match relative {
+            RelativeDirection::Front => self,
+            RelativeDirection::Right => self.turn_right(),
+            RelativeDirection::Back => self.opposite(),
+            RelativeDirection::Left => self.turn_left(),
+        }
+    }
+
+    /// Décalage ligne/colonne correspondant à la direction.
+    pub fn as_offset(self) -> (i64, i64) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::South => (1, 0),
+            Direction::East => (0, 1),
+            Direction::West => (0, -1),
+        }
+    }
+}
+
+/// Erreurs de décodage d'un labyrinthe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MazeError {
+    /// La chaîne n'est pas un base64 valide pour l'alphabet du protocole.
+    InvalidEncoding(b64::DecodeError),
+    /// Le nombre d'octets décodés ne correspond pas aux dimensions annoncées.
+    InvalidLength { expected: usize, actual: usize },
+    /// Un passage porte la valeur 0b11, qui n'a pas de signification.
+    InvalidPassage(usize),
+    /// Les bits qui suivent le dernier passage d'une liste ne sont pas nuls.
+    NonZeroPadding,
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeError::InvalidEncoding(e) => write!(f, "labyrinthe mal encodé: {}", e),
+            MazeError::InvalidLength { expected, actual } => {
+                write!(f, "labyrinthe de {} octets (attendu {})", actual, expected)
+            }
+            MazeError::InvalidPassage(index) => write!(f, "passage {} invalide", index),
+            MazeError::NonZeroPadding => write!(f, "bits de remplissage non nuls après le dernier passage"),
+        }
+    }
+}
+
+impl std::error::Error for MazeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MazeError::InvalidEncoding(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Nombre maximal de lignes ou de colonnes : les dimensions sont encodées sur 16 bits.
+pub const MAX_SIDE: usize = u16::MAX as usize;
+
+/// Nombre d'octets nécessaires pour `count` passages de 2 bits.
+fn passage_bytes(count: usize) -> usize {
+    count.div_ceil(4)
+}
+
+/// Labyrinthe dont l'état de chaque arête entre deux cellules est connu, ouvert, fermé ou inconnu,
+/// comme dans l'encodage `encode_labyrinth` et les vues radar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maze {
+    rows: usize,
+    columns: usize,
+    /// Passages horizontaux : `horizontal[r * columns + c]` est au nord de (r, c), `r` dans `0..=rows`.
+    horizontal: Vec<Passage>,
+    /// Passages verticaux : `vertical[r * (columns + 1) + c]` est à l'ouest de (r, c), `c` dans `0..=columns`.
+    vertical: Vec<Passage>,
+}
+
+impl Maze {
+    /// Labyrinthe dont aucun passage n'est encore connu.
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Self::filled(rows, columns, Passage::Undefined)
+    }
+
+    /// Labyrinthe dont tous les passages, bords compris, valent `passage`.
+    pub fn filled(rows: usize, columns: usize, passage: Passage) -> Self {
+        assert!(rows <= MAX_SIDE && columns <= MAX_SIDE, "labyrinthe de {}x{} trop grand", rows, columns);
+        Self {
+            rows,
+            columns,
+            horizontal: vec![passage; (rows + 1) * columns],
+            vertical: vec![passage; rows * (columns + 1)],
+        }
+    }
+
+    /// Construit un labyrinthe à partir de ses passages, dans l'ordre de [`Maze::horizontal_passages`]
+    /// et [`Maze::vertical_passages`].
+    pub fn from_passages(rows: usize, columns: usize, horizontal: Vec<Passage>, vertical: Vec<Passage>) -> Self {
+        assert!(rows <= MAX_SIDE && columns <= MAX_SIDE, "labyrinthe de {}x{} trop grand", rows, columns);
+        assert_eq!(horizontal.len(), (rows + 1) * columns, "nombre de passages horizontaux");
+        assert_eq!(vertical.len(), rows * (columns + 1), "nombre de passages verticaux");
+        Self { rows, columns, horizontal, vertical }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        pos.0 < self.rows && pos.1 < self.columns
+    }
+
+    /// Itère sur toutes les cellules, ligne par ligne.
+    pub fn cells(&self) -> impl Iterator<Item = Position> {
+        let columns = self.columns;
+        (0..self.rows).flat_map(move |r| (0..columns).map(move |c| (r, c)))
+    }
+
+    fn index(&self, (r, c): Position, dir: Direction) -> (bool, usize) {
+        match dir {
+            Direction::North => (true, r * self.columns + c),
+            Direction::South => (true, (r + 1) * self.columns + c),
+            Direction::West => (false, r * (self.columns + 1) + c),
+            Direction::East => (false, r * (self.columns + 1) + c + 1),
+        }
+    }
+
+    /// Passage du côté `dir` de la cellule `pos`.
+    pub fn passage(&self, pos: Position, dir: Direction) -> Passage {
+        match self.index(pos, dir) {
+            (true, i) => self.horizontal[i],
+            (false, i) => self.vertical[i],
+        }
+    }
+
+    /// Modifie le passage du côté `dir` de la cellule `pos` (il est partagé avec la cellule voisine).
+    pub fn set_passage(&mut self, pos: Position, dir: Direction, passage: Passage) {
+        match self.index(pos, dir) {
+            (true, i) => self.horizontal[i] = passage,
+            (false, i) => self.vertical[i] = passage,
+        }
+    }
+
+    /// Cellule voisine dans la direction `dir`, si elle est dans le labyrinthe (passages ignorés).
+    pub fn neighbour(&self, pos: Position, dir: Direction) -> Option<Position> {
+        let (dr, dc) = dir.as_offset();
+        let r = usize::try_from(pos.0 as i64 + dr).ok()?;
+        let c = usize::try_from(pos.1 as i64 + dc).ok()?;
+        self.contains((r, c)).then_some((r, c))
+    }
+
+    /// Cellule atteinte en quittant `pos` par `dir`, si le passage est connu comme ouvert.
+    pub fn step(&self, pos: Position, dir: Direction) -> Option<Position> {
+        if self.passage(pos, dir) == Passage::Open {
+            self.neighbour(pos, dir)
+        } else {
+            None
+        }
+    }
+
+    /// Passage horizontal `col` de la ligne de bords `row` (0 = bord nord, `rows` = bord sud).
+    pub fn horizontal(&self, row: usize, col: usize) -> Passage {
+        assert!(row <= self.rows && col < self.columns, "passage horizontal ({}, {}) hors du labyrinthe", row, col);
+        self.horizontal[row * self.columns + col]
+    }
+
+    /// Passage vertical `col` de la ligne `row` (0 = bord ouest, `columns` = bord est).
+    pub fn vertical(&self, row: usize, col: usize) -> Passage {
+        assert!(row < self.rows && col <= self.columns, "passage vertical ({}, {}) hors du labyrinthe", row, col);
+        self.vertical[row * (self.columns + 1) + col]
+    }
+
+    pub fn set_horizontal(&mut self, row: usize, col: usize, passage: Passage) {
+        assert!(row <= self.rows && col < self.columns, "passage horizontal ({}, {}) hors du labyrinthe", row, col);
+        self.horizontal[row * self.columns + col] = passage;
+    }
+
+    pub fn set_vertical(&mut self, row: usize, col: usize, passage: Passage) {
+        assert!(row < self.rows && col <= self.columns, "passage vertical ({}, {}) hors du labyrinthe", row, col);
+        self.vertical[row * (self.columns + 1) + col] = passage;
+    }
+
+    /// Passages horizontaux, ligne par ligne du bord nord au bord sud.
+    pub fn horizontal_passages(&self) -> &[Passage] {
+        &self.horizontal
+    }
+
+    /// Passages verticaux, ligne par ligne, du bord ouest au bord est.
+    pub fn vertical_passages(&self) -> &[Passage] {
+        &self.vertical
+    }
+
+    /// Octets de la carte : `nx` (colonnes) et `ny` (lignes) en u16 little-endian,
+    /// puis les passages horizontaux et verticaux sur 2 bits.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + passage_bytes(self.horizontal.len()) + passage_bytes(self.vertical.len()));
+        bytes.extend((self.columns as u16).to_le_bytes());
+        bytes.extend((self.rows as u16).to_le_bytes());
+        bytes.extend(encode_passages(&self.horizontal));
+        bytes.extend(encode_passages(&self.vertical));
+        bytes
+    }
+
+    /// Encode le labyrinthe au format `encode_labyrinth` (passages sur 2 bits).
+    pub fn encode(&self) -> String {
+        b64::encode(&self.to_bytes())
+    }
+
+    /// Construit un labyrinthe de `nx` colonnes et `ny` lignes à partir de ses passages empaquetés,
+    /// en vérifiant la taille de chaque liste, les valeurs des passages et les bits de remplissage.
+    pub fn from_packed(nx: u16, ny: u16, horizontal: &[u8], vertical: &[u8]) -> Result<Self, MazeError> {
+        let (rows, columns) = (ny as usize, nx as usize);
+        let horizontal_count = (rows + 1) * columns;
+        let vertical_count = rows * (columns + 1);
+        for (bytes, count) in [(horizontal, horizontal_count), (vertical, vertical_count)] {
+            if bytes.len() != passage_bytes(count) {
+                return Err(MazeError::InvalidLength { expected: passage_bytes(count), actual: bytes.len() });
+            }
+        }
+        Ok(Self {
+            rows,
+            columns,
+            horizontal: checked_passages(horizontal, horizontal_count, 0)?,
+            vertical: checked_passages(vertical, vertical_count, horizontal_count)?,
+        })
+    }
+
+    /// Décode une carte produite par [`Maze::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MazeError> {
+        if bytes.len() < 4 {
+            return Err(MazeError::InvalidLength { expected: 4, actual: bytes.len() });
+        }
+        let nx = u16::from_le_bytes([bytes[0], bytes[1]]);
+        let ny = u16::from_le_bytes([bytes[2], bytes[3]]);
+        let horizontal_bytes = passage_bytes((ny as usize + 1) * nx as usize);
+        let expected = 4 + horizontal_bytes + passage_bytes(ny as usize * (nx as usize + 1));
+        if bytes.len() != expected {
+            return Err(MazeError::InvalidLength { expected, actual: bytes.len() });
+        }
+        let (horizontal, vertical) = bytes[4..].split_at(horizontal_bytes);
+        Self::from_packed(nx, ny, horizontal, vertical)
+    }
+
+    /// Décode un labyrinthe encodé par `encode_labyrinth`.
+    pub fn decode(encoded: &str) -> Result<Self, MazeError> {
+        Self::from_bytes(&b64::decode(encoded).map_err(MazeError::InvalidEncoding)?)
+    }
+}
+
+/// Nom du format d'`encode_labyrinth` : un [`Maze`] complet, lu par `Labyrinth::decode` et écrit par `encode`.
+/// Aucun message du protocole ne transporte encore la carte ; le serveur ne fait que la journaliser.
+pub type Labyrinth = Maze;
+
+/// Décode `count` passages en refusant la valeur 0b11 et les bits non nuls après le dernier passage ;
+/// `offset` numérote les passages dans les erreurs.
+fn checked_passages(bytes: &[u8], count: usize, offset: usize) -> Result<Vec<Passage>, MazeError> {
+    for i in 0..count {
+        if (bytes[i / 4] >> (6 - (i % 4) * 2)) & 0b11 == 0b11 {
+            return Err(MazeError::InvalidPassage(offset + i));
+        }
+    }
+    if !count.is_multiple_of(4) && bytes[count / 4] & (0xFF >> ((count % 4) * 2)) != 0 {
+        return Err(MazeError::NonZeroPadding);
+    }
+    Ok(decode_passages(bytes, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn passage_strategy() -> impl Strategy<Value = Passage> {
+        prop_oneof![Just(Passage::Undefined), Just(Passage::Open), Just(Passage::Wall)]
+    }
+
+    fn maze_strategy() -> impl Strategy<Value = Maze> {
+        (1usize..12, 1usize..12).prop_flat_map(|(rows, columns)| {
+            (
+                proptest::collection::vec(passage_strategy(), (rows + 1) * columns),
+                proptest::collection::vec(passage_strategy(), rows * (columns + 1)),
+            )
+                .prop_map(move |(h, v)| Maze::from_passages(rows, columns, h, v))
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_encode_decode_round_trip(maze in maze_strategy()) {
+            prop_assert_eq!(Labyrinth::decode(&maze.encode()), Ok(maze));
+        }
+
+        #[test]
+        fn prop_typed_accessors_match_cell_sides(maze in maze_strategy()) {
+            for (r, c) in maze.cells() {
+                prop_assert_eq!(maze.horizontal(r, c), maze.passage((r, c), Direction::North));
+                prop_assert_eq!(maze.horizontal(r + 1, c), maze.passage((r, c), Direction::South));
+                prop_assert_eq!(maze.vertical(r, c), maze.passage((r, c), Direction::West));
+                prop_assert_eq!(maze.vertical(r, c + 1), maze.passage((r, c), Direction::East));
+            }
+        }
+
+        /// Des octets quelconques sont refusés ou décodés en un labyrinthe qui les réencode à l'identique.
+        #[test]
+        fn prop_decode_arbitrary_bytes(
+            nx in 0u16..8,
+            ny in 0u16..8,
+            body in proptest::collection::vec(any::<u8>(), 0..40),
+        ) {
+            let mut bytes = nx.to_le_bytes().to_vec();
+            bytes.extend(ny.to_le_bytes());
+            bytes.extend(body);
+            if let Ok(maze) = Maze::decode(&b64::encode(&bytes)) {
+                prop_assert_eq!(maze.to_bytes(), bytes);
+            }
+        }
+
+        #[test]
+        fn prop_decode_corrupted_encoding(maze in maze_strategy(), index in any::<prop::sample::Index>(), byte in any::<u8>()) {
+            let mut bytes = maze.to_bytes();
+            let i = index.index(bytes.len());
+            bytes[i] = byte;
+            if let Ok(decoded) = Maze::decode(&b64::encode(&bytes)) {
+                prop_assert_eq!(decoded.to_bytes(), bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(Direction::North.turn(&RelativeDirection::Right), Direction::East);
+        assert_eq!(Direction::East.turn(&RelativeDirection::Back), Direction::West);
+        assert_eq!(Direction::South.turn(&RelativeDirection::Left), Direction::East);
+        assert_eq!(Direction::West.turn(&RelativeDirection::Front), Direction::West);
+    }
+
+    #[test]
+    fn test_shared_edges() {
+        let mut maze = Maze::filled(2, 3, Passage::Wall);
+        maze.set_passage((0, 1), Direction::South, Passage::Open);
+        maze.set_passage((1, 1), Direction::East, Passage::Undefined);
+        assert_eq!(maze.passage((1, 1), Direction::North), Passage::Open);
+        assert_eq!(maze.passage((1, 2), Direction::West), Passage::Undefined);
+        assert_eq!(maze.step((1, 1), Direction::North), Some((0, 1)));
+        assert_eq!(maze.step((1, 1), Direction::East), None);
+        assert_eq!(maze.neighbour((0, 0), Direction::West), None);
+    }
+
+    #[test]
+    fn test_typed_accessors() {
+        let mut maze = Maze::filled(2, 3, Passage::Wall);
+        maze.set_horizontal(1, 2, Passage::Open);
+        maze.set_vertical(0, 3, Passage::Undefined);
+        assert_eq!(maze.passage((0, 2), Direction::South), Passage::Open);
+        assert_eq!(maze.passage((1, 2), Direction::North), Passage::Open);
+        assert_eq!(maze.passage((0, 2), Direction::East), Passage::Undefined);
+        assert_eq!(maze.horizontal(2, 2), Passage::Wall);
+        assert_eq!(maze.vertical(1, 0), Passage::Wall);
+    }
+
+    #[test]
+    #[should_panic(expected = "hors du labyrinthe")]
+    fn test_vertical_out_of_bounds() {
+        Maze::new(2, 3).vertical(2, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "trop grand")]
+    fn test_size_limit() {
+        Maze::new(MAX_SIDE + 1, 1);
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_data() {
+        let maze = Maze::new(3, 2);
+        let mut bytes = b64::decode(&maze.encode()).unwrap();
+        bytes.push(0);
+        assert_eq!(
+            Maze::decode(&b64::encode(&bytes)),
+            Err(MazeError::InvalidLength { expected: 9, actual: 10 })
+        );
+        bytes.pop();
+        bytes[4] = 0b0011_0000;
+        assert_eq!(Maze::decode(&b64::encode(&bytes)), Err(MazeError::InvalidPassage(1)));
+        // 3 × 2 : 8 passages horizontaux sur 2 octets pleins, 9 verticaux avec 6 bits de remplissage.
+        bytes[4] = 0;
+        bytes[8] = 0b0000_0001;
+        assert_eq!(Maze::decode(&b64::encode(&bytes)), Err(MazeError::NonZeroPadding));
+        assert!(matches!(Maze::decode("a"), Err(MazeError::InvalidEncoding(_))));
+        assert_eq!(Maze::decode("aaa"), Err(MazeError::InvalidLength { expected: 4, actual: 2 }));
+    }
+}
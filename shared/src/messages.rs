@@ -1,47 +1,55 @@
 use serde::{Serialize, Deserialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Enveloppe de tous les messages échangés entre client et serveur.
+/// La sérialisation est « externally tagged » : `{"RegisterTeam": {...}}`, `{"RadarView": "..."}`, etc.
+/// Les enums imbriquées le sont aussi : une variante unitaire devient son nom (`"SOSHelper"`),
+/// les autres un objet à une clé (`{"MoveTo": "Right"}`). Voir le module `conformance`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Message {
+    RegisterTeam(RegisterTeam),
+    RegisterTeamResult(RegisterTeamResult),
+    SubscribePlayer(SubscribePlayer),
+    SubscribePlayerResult(SubscribePlayerResult),
+    RadarView(RadarView),
+    Hint(Hint),
+    Action(Action),
+    ActionError(ActionError),
+    Challenge(Challenge),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct RegisterTeam {
     pub name: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct RegisterTeamResultOk {
     pub expected_players: u8,
     pub registration_token: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum RegisterTeamResult {
-    OkVariant { #[serde(rename = "Ok")] ok: RegisterTeamResultOk },
-    ErrVariant { #[serde(rename = "Err")] err: String },
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct RegisterTeamResultWrapper {
-    #[serde(rename = "RegisterTeamResult")]
-    pub register_team_result: RegisterTeamResult,
+    Ok(RegisterTeamResultOk),
+    Err(String),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct SubscribePlayer {
     pub name: String,
     pub registration_token: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum SubscribePlayerResult {
     Ok,
     Err(String),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct RadarView(pub String);
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum Hint {
     RelativeCompass { angle: f32 },
     GridSize { columns: u32, rows: u32 },
@@ -49,8 +57,7 @@ pub enum Hint {
     SOSHelper,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum Action {
     MoveTo(RelativeDirection),
     SolveChallenge { answer: String },
@@ -64,8 +71,7 @@ pub enum RelativeDirection {
     Left,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum ActionError {
     CannotPassThroughWall,
     CannotPassThroughOpponent,
@@ -74,9 +80,94 @@ pub enum ActionError {
     InvalidChallengeSolution,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum Challenge {
     SecretSumModulo(u64),
     SOS,
 }
+
+/// Réponse à `SecretSumModulo(modulo)` : somme des secrets de l'équipe modulo `modulo`,
+/// calculée sans débordement. `modulo` ne doit pas être nul.
+pub fn secret_sum_modulo(secrets: impl IntoIterator<Item = u64>, modulo: u64) -> u64 {
+    let modulo = modulo as u128;
+    secrets.into_iter().fold(0u128, |acc, s| (acc + s as u128) % modulo) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(msg: Message) {
+        let json = serde_json::to_string(&msg).expect("sérialisation");
+        let back: Message = serde_json::from_str(&json).expect("désérialisation");
+        assert_eq!(back, msg, "aller-retour raté pour {}", json);
+    }
+
+    #[test]
+    fn test_secret_sum_modulo_does_not_overflow() {
+        assert_eq!(secret_sum_modulo([u64::MAX, u64::MAX, 2], 10), ((u64::MAX as u128 * 2 + 2) % 10) as u64);
+        assert_eq!(secret_sum_modulo([], 7), 0);
+    }
+
+    #[test]
+    fn test_round_trip_registration() {
+        round_trip(Message::RegisterTeam(RegisterTeam { name: "curious_broccoli".into() }));
+        round_trip(Message::RegisterTeamResult(RegisterTeamResult::Ok(RegisterTeamResultOk {
+            expected_players: 3,
+            registration_token: "SECRET".into(),
+        })));
+        round_trip(Message::RegisterTeamResult(RegisterTeamResult::Err("AlreadyRegistered".into())));
+        round_trip(Message::SubscribePlayer(SubscribePlayer {
+            name: "alice".into(),
+            registration_token: "SECRET".into(),
+        }));
+        round_trip(Message::SubscribePlayerResult(SubscribePlayerResult::Ok));
+        round_trip(Message::SubscribePlayerResult(SubscribePlayerResult::Err("InvalidRegistrationToken".into())));
+    }
+
+    #[test]
+    fn test_round_trip_game() {
+        round_trip(Message::RadarView(RadarView("ieysGjGO8papd/a".into())));
+        round_trip(Message::Hint(Hint::RelativeCompass { angle: 12.5 }));
+        round_trip(Message::Hint(Hint::GridSize { columns: 10, rows: 8 }));
+        round_trip(Message::Hint(Hint::Secret(42)));
+        round_trip(Message::Hint(Hint::SOSHelper));
+        for dir in [RelativeDirection::Front, RelativeDirection::Right, RelativeDirection::Back, RelativeDirection::Left] {
+            round_trip(Message::Action(Action::MoveTo(dir)));
+        }
+        round_trip(Message::Action(Action::SolveChallenge { answer: "42".into() }));
+        round_trip(Message::Challenge(Challenge::SecretSumModulo(17)));
+        round_trip(Message::Challenge(Challenge::SOS));
+    }
+
+    #[test]
+    fn test_round_trip_action_errors() {
+        for err in [
+            ActionError::CannotPassThroughWall,
+            ActionError::CannotPassThroughOpponent,
+            ActionError::NoRunningChallenge,
+            ActionError::SolveChallengeFirst,
+            ActionError::InvalidChallengeSolution,
+        ] {
+            round_trip(Message::ActionError(err));
+        }
+    }
+
+    #[test]
+    fn test_envelope_is_externally_tagged() {
+        let msg = Message::RegisterTeam(RegisterTeam { name: "RegisterTeam".into() });
+        let json = serde_json::to_string(&msg).unwrap();
+        assert_eq!(json, r#"{"RegisterTeam":{"name":"RegisterTeam"}}"#);
+
+        let json = serde_json::to_string(&Message::ActionError(ActionError::CannotPassThroughWall)).unwrap();
+        assert_eq!(json, r#"{"ActionError":"CannotPassThroughWall"}"#);
+    }
+
+    #[test]
+    fn test_team_name_does_not_confuse_dispatch() {
+        // Un nom d'équipe contenant "RegisterTeam" ne doit pas influencer le type du message.
+        let json = r#"{"SubscribePlayer":{"name":"RegisterTeam","registration_token":"\"RegisterTeam\""}}"#;
+        let msg: Message = serde_json::from_str(json).unwrap();
+        assert!(matches!(msg, Message::SubscribePlayer(_)));
+    }
+}
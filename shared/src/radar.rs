@@ -0,0 +1,389 @@
+use std::fmt;
+
+use crate::encodings::{b64, encode_radar};
+use crate::messages::RelativeDirection;
+
+/// État d'un passage entre deux cellules (2 bits).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Passage {
+    Undefined,
+    Open,
+    Wall,
+}
+
+/// Contenu d'une cellule de la vue radar (4 bits).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadarItem {
+    None,
+    Ally,
+    Enemy,
+    Monster,
+    Hint,
+    Target,
+}
+
+impl RadarItem {
+    pub fn to_bits(&self) -> u8 {
+        match *self {
+            RadarItem::None => 0b0000,
+            RadarItem::Ally => 0b0001,
+            RadarItem::Enemy => 0b0010,
+            RadarItem::Monster => 0b0011,
+            RadarItem::Hint => 0b0100,
+            RadarItem::Target => 0b1000,
+        }
+    }
+
+    pub fn from_bits(bits: u8) -> RadarItem {
+        match bits {
+            0b0000 => RadarItem::None,
+            0b0001 => RadarItem::Ally,
+            0b0010 => RadarItem::Enemy,
+            0b0011 => RadarItem::Monster,
+            0b0100 => RadarItem::Hint,
+            0b1000 => RadarItem::Target,
+            _ => RadarItem::None,
+        }
+    }
+}
+
+impl Passage {
+    pub fn to_bits(&self) -> u8 {
+        match *self {
+            Passage::Undefined => 0b00,
+            Passage::Open => 0b01,
+            Passage::Wall => 0b10,
+        }
+    }
+
+    pub fn from_bits(bits: u8) -> Passage {
+        match bits {
+            0b00 => Passage::Undefined,
+            0b01 => Passage::Open,
+            0b10 => Passage::Wall,
+            _ => Passage::Undefined,
+        }
+    }
+}
+
+pub fn encode_passages(passages: &[Passage]) -> Vec<u8> {
+    let mut bits = Vec::new();
+    for passage in passages {
+        bits.push(passage.to_bits());
+    }
+
+    let mut result = Vec::new();
+    for chunk in bits.chunks(4) {
+        let mut byte = 0u8;
+        for (i, &bit) in chunk.iter().enumerate() {
+            byte |= bit << (6 - i * 2);
+        }
+        result.push(byte);
+    }
+
+    result
+}
+
+pub fn encode_radar_items(items: &[RadarItem]) -> Vec<u8> {
+    let mut bits = Vec::new();
+    for item in items {
+        bits.push(item.to_bits());
+    }
+
+    let mut result = Vec::new();
+    for chunk in bits.chunks(2) {
+        let mut byte = 0u8;
+        for (i, &bit) in chunk.iter().enumerate() {
+            byte |= bit << (4 - i * 4);
+        }
+        result.push(byte);
+    }
+
+    result
+}
+
+pub fn decode_passages(bytes: &[u8], expected_count: usize) -> Vec<Passage> {
+    let mut passages = Vec::new();
+    for &byte in bytes {
+        for i in 0..4 {
+            if passages.len() >= expected_count {
+                break;
+            }
+            let passage_bits = (byte >> (6 - i * 2)) & 0b11;
+            passages.push(Passage::from_bits(passage_bits));
+        }
+    }
+    passages
+}
+
+pub fn decode_radar_items(bytes: &[u8], expected_count: usize) -> Vec<RadarItem> {
+    let mut items = Vec::new();
+    for &byte in bytes {
+        for i in 0..2 {
+            if items.len() >= expected_count {
+                break;
+            }
+            let radar_bits = (byte >> (4 - i * 4)) & 0b1111;
+            items.push(RadarItem::from_bits(radar_bits));
+        }
+    }
+    items
+}
+
+/// Nombre de passages horizontaux (4 lignes de 3) et verticaux (3 lignes de 4) dans une vue radar.
+pub const RADAR_PASSAGES: usize = 12;
+/// Nombre de cellules d'une vue radar (3 × 3).
+pub const RADAR_CELLS: usize = 9;
+/// Taille en octets d'une vue radar décodée : 3 + 3 + 5.
+const RADAR_BYTES: usize = 11;
+
+/// Erreurs de décodage d'une vue radar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RadarError {
+    /// La chaîne n'est pas un base64 valide pour l'alphabet du protocole.
+    InvalidEncoding(b64::DecodeError),
+    /// Le nombre d'octets décodés ne correspond pas au format radar.
+    InvalidLength(usize),
+}
+
+impl fmt::Display for RadarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RadarError::InvalidEncoding(e) => write!(f, "vue radar mal encodée: {}", e),
+            RadarError::InvalidLength(n) => {
+                write!(f, "vue radar de {} octets (attendu {})", n, RADAR_BYTES)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RadarError {}
+
+/// Case de la représentation 7 × 7 d'une vue radar : les cellules occupent les indices impairs,
+/// les passages les positions intermédiaires et les coins les indices pairs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadarTile {
+    Corner,
+    Passage(Passage),
+    Cell(RadarItem),
+}
+
+/// Vue radar décodée : 3 × 3 cellules centrées sur le joueur, dans son repère
+/// (le haut de la grille correspond à `RelativeDirection::Front`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RadarGrid {
+    /// Passages horizontaux, ligne par ligne : `horizontal[row * 3 + col]`, `row` dans `0..4`.
+    horizontal: [Passage; RADAR_PASSAGES],
+    /// Passages verticaux, ligne par ligne : `vertical[row * 4 + col]`, `col` dans `0..4`.
+    vertical: [Passage; RADAR_PASSAGES],
+    /// Cellules, ligne par ligne : `cells[row * 3 + col]`.
+    cells: [RadarItem; RADAR_CELLS],
+}
+
+impl Default for RadarGrid {
+    fn default() -> Self {
+        Self {
+            horizontal: [Passage::Undefined; RADAR_PASSAGES],
+            vertical: [Passage::Undefined; RADAR_PASSAGES],
+            cells: [RadarItem::None; RADAR_CELLS],
+        }
+    }
+}
+
+impl RadarGrid {
+    pub fn new(
+        horizontal: [Passage; RADAR_PASSAGES],
+        vertical: [Passage; RADAR_PASSAGES],
+        cells: [RadarItem; RADAR_CELLS],
+    ) -> Self {
+        Self { horizontal, vertical, cells }
+    }
+
+    /// Décode la chaîne reçue dans un message `RadarView`.
+    pub fn decode(encoded: &str) -> Result<Self, RadarError> {
+        let bytes = b64::decode(encoded).map_err(RadarError::InvalidEncoding)?;
+        if bytes.len() != RADAR_BYTES {
+            return Err(RadarError::InvalidLength(bytes.len()));
+        }
+        let mut grid = RadarGrid::default();
+        for (slot, p) in grid.horizontal.iter_mut().zip(decode_passages(&bytes[0..3], RADAR_PASSAGES)) {
+            *slot = p;
+        }
+        for (slot, p) in grid.vertical.iter_mut().zip(decode_passages(&bytes[3..6], RADAR_PASSAGES)) {
+            *slot = p;
+        }
+        for (slot, item) in grid.cells.iter_mut().zip(decode_radar_items(&bytes[6..11], RADAR_CELLS)) {
+            *slot = item;
+        }
+        Ok(grid)
+    }
+
+    /// Réencode la vue au format du protocole.
+    pub fn encode(&self) -> String {
+        let mut horiz = [0u8; 3];
+        horiz.copy_from_slice(&encode_passages(&self.horizontal));
+        let mut vert = [0u8; 3];
+        vert.copy_from_slice(&encode_passages(&self.vertical));
+        let mut cells = [0u8; 5];
+        cells.copy_from_slice(&encode_radar_items(&self.cells));
+        encode_radar(&horiz, &vert, &cells)
+    }
+
+    /// Passage horizontal `col` de la ligne `row` (0 = bord haut de la vue, 3 = bord bas).
+    pub fn horizontal(&self, row: usize, col: usize) -> Passage {
+        self.horizontal[row * 3 + col]
+    }
+
+    /// Passage vertical `col` de la ligne `row` (0 = bord gauche de la vue, 3 = bord droit).
+    pub fn vertical(&self, row: usize, col: usize) -> Passage {
+        self.vertical[row * 4 + col]
+    }
+
+    pub fn set_horizontal(&mut self, row: usize, col: usize, passage: Passage) {
+        self.horizontal[row * 3 + col] = passage;
+    }
+
+    pub fn set_vertical(&mut self, row: usize, col: usize, passage: Passage) {
+        self.vertical[row * 4 + col] = passage;
+    }
+
+    /// Contenu de la cellule (`row`, `col`), avec (1, 1) la cellule du joueur.
+    pub fn item(&self, row: usize, col: usize) -> RadarItem {
+        self.cells[row * 3 + col]
+    }
+
+    pub fn set_item(&mut self, row: usize, col: usize, item: RadarItem) {
+        self.cells[row * 3 + col] = item;
+    }
+
+    /// Passage bordant la cellule (`row`, `col`) du côté `side`, dans le repère du joueur.
+    pub fn passage(&self, row: usize, col: usize, side: RelativeDirection) -> Passage {
+        match side {
+            RelativeDirection::Front => self.horizontal(row, col),
+            RelativeDirection::Back => self.horizontal(row + 1, col),
+            RelativeDirection::Left => self.vertical(row, col),
+            RelativeDirection::Right => self.vertical(row, col + 1),
+        }
+    }
+
+    pub fn is_wall(&self, row: usize, col: usize, side: RelativeDirection) -> bool {
+        self.passage(row, col, side) == Passage::Wall
+    }
+
+    pub fn is_open(&self, row: usize, col: usize, side: RelativeDirection) -> bool {
+        self.passage(row, col, side) == Passage::Open
+    }
+
+    pub fn is_undefined(&self, row: usize, col: usize, side: RelativeDirection) -> bool {
+        self.passage(row, col, side) == Passage::Undefined
+    }
+
+    /// Passage entre le joueur et la cellule voisine dans la direction `dir`.
+    pub fn player_passage(&self, dir: RelativeDirection) -> Passage {
+        self.passage(1, 1, dir)
+    }
+
+    /// Représentation 7 × 7 de la vue : `tiles()[y][x]`.
+    pub fn tiles(&self) -> [[RadarTile; 7]; 7] {
+        let mut tiles = [[RadarTile::Corner; 7]; 7];
+        for (y, line) in tiles.iter_mut().enumerate() {
+            for (x, tile) in line.iter_mut().enumerate() {
+                *tile = match (y % 2, x % 2) {
+                    (0, 0) => RadarTile::Corner,
+                    (0, _) => RadarTile::Passage(self.horizontal(y / 2, x / 2)),
+                    (_, 0) => RadarTile::Passage(self.vertical(y / 2, x / 2)),
+                    _ => RadarTile::Cell(self.item(y / 2, x / 2)),
+                };
+            }
+        }
+        tiles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn passage_strategy() -> impl Strategy<Value = Passage> {
+        prop_oneof![Just(Passage::Undefined), Just(Passage::Open), Just(Passage::Wall)]
+    }
+
+    fn item_strategy() -> impl Strategy<Value = RadarItem> {
+        prop_oneof![
+            Just(RadarItem::None),
+            Just(RadarItem::Ally),
+            Just(RadarItem::Enemy),
+            Just(RadarItem::Monster),
+            Just(RadarItem::Hint),
+            Just(RadarItem::Target),
+        ]
+    }
+
+    fn grid_strategy() -> impl Strategy<Value = RadarGrid> {
+        (
+            proptest::array::uniform12(passage_strategy()),
+            proptest::array::uniform12(passage_strategy()),
+            proptest::array::uniform9(item_strategy()),
+        )
+            .prop_map(|(h, v, c)| RadarGrid::new(h, v, c))
+    }
+
+    proptest! {
+        #[test]
+        fn prop_grid_round_trip(grid in grid_strategy()) {
+            let encoded = grid.encode();
+            prop_assert_eq!(encoded.len(), 15);
+            prop_assert_eq!(RadarGrid::decode(&encoded).unwrap(), grid);
+        }
+
+        #[test]
+        fn prop_decode_encode_is_stable(grid in grid_strategy()) {
+            let encoded = grid.encode();
+            let decoded = RadarGrid::decode(&encoded).unwrap();
+            prop_assert_eq!(decoded.encode(), encoded);
+        }
+    }
+
+    #[test]
+    fn test_decode_layout() {
+        let mut grid = RadarGrid::default();
+        grid.set_horizontal(1, 1, Passage::Wall);
+        grid.set_vertical(1, 2, Passage::Open);
+        grid.set_item(1, 1, RadarItem::Ally);
+        grid.set_item(2, 0, RadarItem::Target);
+        let decoded = RadarGrid::decode(&grid.encode()).unwrap();
+
+        assert!(decoded.is_wall(1, 1, RelativeDirection::Front));
+        assert!(decoded.is_wall(0, 1, RelativeDirection::Back));
+        assert!(decoded.is_open(1, 1, RelativeDirection::Right));
+        assert!(decoded.is_open(1, 2, RelativeDirection::Left));
+        assert!(decoded.is_undefined(1, 1, RelativeDirection::Left));
+        assert_eq!(decoded.item(1, 1), RadarItem::Ally);
+        assert_eq!(decoded.item(2, 0), RadarItem::Target);
+
+        let tiles = decoded.tiles();
+        assert_eq!(tiles[3][3], RadarTile::Cell(RadarItem::Ally));
+        assert_eq!(tiles[2][3], RadarTile::Passage(Passage::Wall));
+        assert_eq!(tiles[3][4], RadarTile::Passage(Passage::Open));
+        assert_eq!(tiles[0][0], RadarTile::Corner);
+    }
+
+    #[test]
+    fn test_decode_bit_order() {
+        // Premier passage horizontal = bits de poids fort du premier octet.
+        let encoded = encode_radar(&[0b1001_0000, 0, 0], &[0, 0, 0b0000_0010], &[0b0001_1000, 0, 0, 0, 0]);
+        let grid = RadarGrid::decode(&encoded).unwrap();
+        assert_eq!(grid.horizontal(0, 0), Passage::Wall);
+        assert_eq!(grid.horizontal(0, 1), Passage::Open);
+        assert_eq!(grid.vertical(2, 3), Passage::Wall);
+        assert_eq!(grid.item(0, 0), RadarItem::Ally);
+        assert_eq!(grid.item(0, 1), RadarItem::Target);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(matches!(RadarGrid::decode("abcde"), Err(RadarError::InvalidEncoding(_))));
+        assert_eq!(RadarGrid::decode("aaaa"), Err(RadarError::InvalidLength(3)));
+    }
+}
@@ -0,0 +1,319 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use crate::messages::Message;
+
+/// Taille maximale d'une trame acceptée par défaut (1 Mio).
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 1_048_576;
+
+/// Erreurs pouvant survenir lors de l'envoi ou de la réception d'une trame.
+#[derive(Debug)]
+pub enum TransportError {
+    /// Le pair a fermé la connexion proprement, entre deux trames.
+    Closed,
+    /// Erreur d'entrée/sortie sur le flux sous-jacent (y compris une trame tronquée).
+    Io(io::Error),
+    /// La trame annoncée (ou à envoyer) dépasse la taille maximale configurée.
+    FrameTooLarge { size: usize, max: usize },
+    /// Le corps de la trame n'est pas un message JSON valide.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportError::Closed => write!(f, "connexion fermée par le pair"),
+            TransportError::Io(e) => write!(f, "erreur d'entrée/sortie: {}", e),
+            TransportError::FrameTooLarge { size, max } => {
+                write!(f, "trame trop grande: {} octets (maximum {})", size, max)
+            }
+            TransportError::Json(e) => write!(f, "message JSON invalide: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TransportError::Io(e) => Some(e),
+            TransportError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TransportError {
+    fn from(e: io::Error) -> Self {
+        TransportError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for TransportError {
+    fn from(e: serde_json::Error) -> Self {
+        TransportError::Json(e)
+    }
+}
+
+/// Flux découpé en trames : chaque message est précédé de sa taille sur 4 octets little-endian,
+/// suivie du corps JSON.
+pub struct FramedStream<S> {
+    stream: S,
+    max_frame_size: usize,
+}
+
+impl<S: Read + Write> FramedStream<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+        }
+    }
+
+    /// Change la taille maximale des trames acceptées et envoyées.
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    /// Sérialise et envoie un message dans une seule trame.
+    pub fn send(&mut self, message: &Message) -> Result<(), TransportError> {
+        let body = serde_json::to_vec(message)?;
+        self.send_frame(&body)
+    }
+
+    /// Lit la trame suivante et la désérialise.
+    /// Une trame dont le JSON est invalide est entièrement consommée : le flux reste utilisable.
+    pub fn recv(&mut self) -> Result<Message, TransportError> {
+        let body = self.recv_frame()?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Envoie un corps brut, déjà sérialisé.
+    pub fn send_frame(&mut self, body: &[u8]) -> Result<(), TransportError> {
+        let frame = encode_frame(body, self.max_frame_size)?;
+        self.stream.write_all(&frame)?;
+        self.stream.flush()?;
+        Ok(())
+    }
+
+    /// Lit le corps brut de la trame suivante.
+    pub fn recv_frame(&mut self) -> Result<Vec<u8>, TransportError> {
+        let mut header = [0u8; 4];
+        let mut filled = 0;
+        while filled < header.len() {
+            match self.stream.read(&mut header[filled..]) {
+                Ok(0) if filled == 0 => return Err(TransportError::Closed),
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let mut body = vec![0u8; frame_size(header, self.max_frame_size)?];
+        self.stream.read_exact(&mut body)?;
+        Ok(body)
+    }
+}
+
+/// Construit une trame complète (en-tête de taille puis corps), prête à être écrite.
+/// Partagé par `FramedStream` et les implémentations asynchrones du protocole.
+pub fn encode_frame(body: &[u8], max_frame_size: usize) -> Result<Vec<u8>, TransportError> {
+    if body.len() > max_frame_size {
+        return Err(TransportError::FrameTooLarge { size: body.len(), max: max_frame_size });
+    }
+    let mut frame = Vec::with_capacity(4 + body.len());
+    frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    frame.extend_from_slice(body);
+    Ok(frame)
+}
+
+/// Taille du corps annoncée par l'en-tête d'une trame, refusée au-delà de `max_frame_size`.
+pub fn frame_size(header: [u8; 4], max_frame_size: usize) -> Result<usize, TransportError> {
+    let size = u32::from_le_bytes(header) as usize;
+    if size > max_frame_size {
+        return Err(TransportError::FrameTooLarge { size, max: max_frame_size });
+    }
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{Action, ActionError, RegisterTeam, RelativeDirection};
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Condvar, Mutex};
+
+    /// Tube en mémoire unidirectionnel, bloquant en lecture tant qu'il est vide et ouvert.
+    #[derive(Default)]
+    struct Channel {
+        data: Mutex<(VecDeque<u8>, bool)>,
+        ready: Condvar,
+    }
+
+    /// Extrémité d'un tube bidirectionnel en mémoire.
+    struct PipeEnd {
+        incoming: Arc<Channel>,
+        outgoing: Arc<Channel>,
+    }
+
+    fn duplex() -> (PipeEnd, PipeEnd) {
+        let a = Arc::new(Channel::default());
+        let b = Arc::new(Channel::default());
+        (
+            PipeEnd { incoming: a.clone(), outgoing: b.clone() },
+            PipeEnd { incoming: b, outgoing: a },
+        )
+    }
+
+    impl Read for PipeEnd {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let mut guard = self.incoming.data.lock().unwrap();
+            while guard.0.is_empty() && !guard.1 {
+                guard = self.incoming.ready.wait(guard).unwrap();
+            }
+            let n = buf.len().min(guard.0.len());
+            for (slot, byte) in buf.iter_mut().zip(guard.0.drain(..n)) {
+                *slot = byte;
+            }
+            Ok(n)
+        }
+    }
+
+    impl Write for PipeEnd {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let mut guard = self.outgoing.data.lock().unwrap();
+            guard.0.extend(buf);
+            self.outgoing.ready.notify_all();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Drop for PipeEnd {
+        fn drop(&mut self) {
+            let mut guard = self.outgoing.data.lock().unwrap();
+            guard.1 = true;
+            self.outgoing.ready.notify_all();
+        }
+    }
+
+    fn register(name: &str) -> Message {
+        Message::RegisterTeam(RegisterTeam { name: name.to_string() })
+    }
+
+    #[test]
+    fn test_send_writes_length_prefix() {
+        let (a, mut b) = duplex();
+        let mut framed = FramedStream::new(a);
+        framed.send(&register("x")).unwrap();
+        drop(framed);
+        let mut raw = Vec::new();
+        b.read_to_end(&mut raw).unwrap();
+        let body = br#"{"RegisterTeam":{"name":"x"}}"#;
+        assert_eq!(&raw[..4], &(body.len() as u32).to_le_bytes());
+        assert_eq!(&raw[4..], body);
+    }
+
+    #[test]
+    fn test_round_trip_between_threads() {
+        let (a, b) = duplex();
+        let server = std::thread::spawn(move || {
+            let mut framed = FramedStream::new(b);
+            while let Ok(msg) = framed.recv() {
+                let reply = match msg {
+                    Message::Action(Action::MoveTo(_)) => Message::ActionError(ActionError::CannotPassThroughWall),
+                    other => other,
+                };
+                framed.send(&reply).unwrap();
+            }
+        });
+        let mut client = FramedStream::new(a);
+        client.send(&register("echo")).unwrap();
+        assert_eq!(client.recv().unwrap(), register("echo"));
+        client.send(&Message::Action(Action::MoveTo(RelativeDirection::Front))).unwrap();
+        assert_eq!(client.recv().unwrap(), Message::ActionError(ActionError::CannotPassThroughWall));
+        drop(client);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_closed_between_frames() {
+        let (a, b) = duplex();
+        drop(a);
+        let mut framed = FramedStream::new(b);
+        assert!(matches!(framed.recv(), Err(TransportError::Closed)));
+    }
+
+    #[test]
+    fn test_truncated_frame_is_io_error() {
+        let (mut a, b) = duplex();
+        a.write_all(&10u32.to_le_bytes()).unwrap();
+        a.write_all(b"{\"Re").unwrap();
+        drop(a);
+        let mut framed = FramedStream::new(b);
+        match framed.recv() {
+            Err(TransportError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            other => panic!("erreur inattendue: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_frame_too_large_on_receive() {
+        let (mut a, b) = duplex();
+        a.write_all(&(DEFAULT_MAX_FRAME_SIZE as u32 + 1).to_le_bytes()).unwrap();
+        let mut framed = FramedStream::new(b);
+        assert!(matches!(
+            framed.recv(),
+            Err(TransportError::FrameTooLarge { size, max }) if size == DEFAULT_MAX_FRAME_SIZE + 1 && max == DEFAULT_MAX_FRAME_SIZE
+        ));
+    }
+
+    #[test]
+    fn test_frame_too_large_on_send() {
+        let (a, _b) = duplex();
+        let mut framed = FramedStream::new(a).with_max_frame_size(8);
+        assert!(matches!(
+            framed.send(&register("trop long pour huit octets")),
+            Err(TransportError::FrameTooLarge { max: 8, .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_json_keeps_stream_usable() {
+        let (a, b) = duplex();
+        let mut writer = FramedStream::new(a);
+        writer.send_frame(b"{pas du json").unwrap();
+        writer.send(&register("ok")).unwrap();
+        let mut reader = FramedStream::new(b);
+        assert!(matches!(reader.recv(), Err(TransportError::Json(_))));
+        assert_eq!(reader.recv().unwrap(), register("ok"));
+    }
+
+    #[test]
+    fn test_codec_helpers() {
+        let frame = encode_frame(b"abc", 3).unwrap();
+        assert_eq!(frame, [3, 0, 0, 0, b'a', b'b', b'c']);
+        assert_eq!(frame_size([3, 0, 0, 0], 3).unwrap(), 3);
+        assert!(matches!(frame_size([4, 0, 0, 0], 3), Err(TransportError::FrameTooLarge { size: 4, max: 3 })));
+        assert!(matches!(encode_frame(b"abcd", 3), Err(TransportError::FrameTooLarge { size: 4, max: 3 })));
+    }
+}